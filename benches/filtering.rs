@@ -0,0 +1,78 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use josaa_orcr::{Catalog, DEFAULT_ROOT, Dataset, Options};
+use std::hint::black_box;
+
+/// Find the largest bundled dataset, by file size.
+fn get_largest(catalog: &Catalog) -> Options {
+    catalog
+        .get_years()
+        .flat_map(|year| {
+            catalog
+                .get_rounds(Some(year))
+                .map(move |round| Options::new(year, round))
+        })
+        .max_by_key(|options| {
+            catalog
+                .get_path(options)
+                .and_then(|path| path.metadata().ok())
+                .map_or(0, |metadata| metadata.len())
+        })
+        .expect("No bundled datasets found.")
+}
+
+fn filtering(c: &mut Criterion) {
+    let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+    let options = get_largest(&catalog);
+
+    let mut dataset = Dataset::default();
+    dataset
+        .load(&options, &catalog.get_paths(&options))
+        .unwrap();
+
+    // Deselect some values, so that not every entry matches.
+    let (filters, dictionaries) = dataset.get_filters_with_dictionaries();
+
+    for quota in ["HS", "OS"] {
+        if let Some(id) = dictionaries.quota.get_id(quota) {
+            filters.quota.set(id as usize, false);
+        }
+    }
+
+    dataset.apply_filters();
+
+    let mut group = c.benchmark_group(format!(
+        "filtering data-{}-{}",
+        options.year.unwrap(),
+        options.round.unwrap()
+    ));
+
+    // What the table used to do every frame
+    group.bench_function("filter every frame", |b| {
+        b.iter(|| {
+            dataset.apply_filters();
+            black_box(
+                dataset
+                    .get_entries()
+                    .map(|entry| entry.cr.get_value())
+                    .sum::<u32>(),
+            )
+        })
+    });
+
+    // What the table does now, unless the filters change
+    group.bench_function("cached filters", |b| {
+        b.iter(|| {
+            black_box(
+                dataset
+                    .get_entries()
+                    .map(|entry| entry.cr.get_value())
+                    .sum::<u32>(),
+            )
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, filtering);
+criterion_main!(benches);
@@ -1,61 +1,281 @@
 use crate::{
-    Dataset, Entry, Options, Sort,
-    widgets::{Dropdown, Multiselect, RangeSelector},
+    BranchFamilies, Candidate, Catalog, Category, Chance, Dataset, Entry, EntryKey, Error,
+    ExportFormat, Gender, Loader, Options, Prediction, QueryResult, STATES, Search, SearchField,
+    Sort, SortColumn, Trend, find_institute,
+    types::{Rank, RankDomain},
+    widgets::{Dropdown, Multiselect, RangeSelector, TreeSelect},
 };
 use egui::Widget;
 use egui_extras::{Column, TableBuilder};
+use egui_plot::{Legend, Line, Plot, PlotPoints, Points};
 
-#[derive(Default)]
 pub struct AppState {
+    catalog: Catalog,
     dataset: Dataset,
     options: Options,
     sort: Sort,
+    /// Whether the table is grouped by the family of each branch, after sorting.
+    group_by_family: bool,
+    trend: Option<Trend>,
+    candidate: Candidate,
+    predicting: bool,
+    /// The predictions for the candidate they were made for, until the filters or the sort change.
+    predictions: Option<(Candidate, Vec<Prediction>)>,
+    sql: String,
+    query_result: Option<crate::Result<QueryResult>>,
+    attach_options: Options,
+
+    /// The last error, shown in a banner until it is dismissed.
+    error: Option<Error>,
+
+    /// The dataset being loaded in the background, if any.
+    loader: Option<Loader>,
+
+    export_format: ExportFormat,
+
+    /// The free-text search above the table, kept when another dataset is loaded.
+    search: String,
+
+    /// The families branches are grouped into when a dataset is loaded.
+    branch_families: BranchFamilies,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        // Falls back to the bundled families, showing why in the error banner.
+        let (branch_families, error) = match BranchFamilies::from_env() {
+            Ok(branch_families) => (branch_families, None),
+            Err(error) => (BranchFamilies::default(), Some(error)),
+        };
+
+        Self {
+            catalog: Catalog::new(),
+            dataset: Dataset::default(),
+            options: Options::default(),
+            sort: Sort::default(),
+            group_by_family: false,
+            trend: None,
+            candidate: Candidate::default(),
+            predicting: false,
+            predictions: None,
+            sql: String::new(),
+            query_result: None,
+            attach_options: Options::default(),
+            error,
+            loader: None,
+            export_format: ExportFormat::default(),
+            search: String::new(),
+            branch_families,
+        }
+    }
+}
+
+impl AppState {
+    /// Sort the table, then group it by family if that is enabled.
+    fn sort(&mut self) {
+        self.dataset.sort(&self.sort);
+
+        if self.group_by_family {
+            self.dataset.group_by_family();
+        }
+
+        self.predictions = None;
+    }
+
+    /// Filter the table again, after the filters were changed.
+    fn apply_filters(&mut self) {
+        self.dataset.apply_filters();
+        self.predictions = None;
+    }
+
+    /// Predict the rows the candidate could get, unless they were already predicted for them.
+    /// Predictions keep the order of the table, and are grouped by family if that is enabled.
+    fn predict(&mut self) {
+        if self
+            .predictions
+            .as_ref()
+            .is_none_or(|(candidate, _)| *candidate != self.candidate)
+        {
+            let mut predictions = self.dataset.predict(&self.candidate, &self.sort);
+
+            if self.group_by_family && self.sort.has_predicted() {
+                predictions.sort_by_key(|prediction| {
+                    self.dataset
+                        .get_entry(prediction.index)
+                        .map(|entry| entry.family)
+                });
+            }
+
+            self.predictions = Some((self.candidate.clone(), predictions));
+        }
+    }
+
+    /// Ask where to save the filtered entries, and export them there.
+    fn export(&mut self) {
+        let format = self.export_format;
+        let name = match (
+            self.dataset.get_options().year,
+            self.dataset.get_options().round,
+        ) {
+            (Some(year), _) if self.dataset.get_options().all_rounds => format!("orcr-{year}"),
+            (Some(year), Some(round)) => format!("orcr-{year}-{round}"),
+            _ => "orcr".into(),
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(format.to_string(), &[format.get_extension()])
+            .set_file_name(format!("{name}.{}", format.get_extension()))
+            .save_file()
+        else {
+            return;
+        };
+
+        if let Err(error) = self.dataset.export_to_file(format, &path) {
+            self.error = Some(error);
+        }
+    }
 }
 
 impl eframe::App for AppState {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // The previous dataset stays visible until the new one has loaded.
+        if let Some(result) = self.loader.as_mut().and_then(Loader::poll) {
+            self.loader = None;
+
+            match result {
+                Ok(dataset) => {
+                    // Institutes stay selected in the new dataset, even if they were renamed.
+                    let previous = self.dataset.get_filters();
+                    let institutes = (previous.institute.count() < previous.institute.len())
+                        .then(|| previous.get_institutes(self.dataset.get_dictionaries()));
+
+                    self.dataset = dataset;
+                    // Query results were from the previous dataset.
+                    self.query_result = None;
+
+                    let (filters, dictionaries) = self.dataset.get_filters_with_dictionaries();
+
+                    if let Some(ids) = institutes {
+                        filters.set_institutes(dictionaries, &ids);
+                        filters.select_institute_kinds(dictionaries);
+                    }
+
+                    filters.set_search(dictionaries, &self.search);
+
+                    self.sort();
+                    self.error = None;
+                }
+                Err(Error::Cancelled) => (),
+                Err(error) => self.error = Some(error),
+            }
+        }
+
+        // Error Banner
+        if let Some(error) = &self.error {
+            let mut dismiss = false;
+
+            egui::TopBottomPanel::top("Error Panel").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {error}"));
+
+                    if ui.button("Dismiss").clicked() {
+                        dismiss = true;
+                    }
+                });
+            });
+
+            if dismiss {
+                self.error = None;
+            }
+        }
+
         // Top Header
         egui::TopBottomPanel::top("Top Panel")
             .resizable(true)
             .show(ctx, |ui| {
+                // Whether any filter was changed this frame.
+                let mut filters_changed = false;
+
                 // Dataset selection
                 egui::CollapsingHeader::new("Choose a dataset")
                     .default_open(true)
                     .show(ui, |ui| {
                         ui.horizontal(|ui| {
                             // Year selection
-                            Dropdown::with_state(&mut self.options.year)
+                            let year_response = Dropdown::with_state(&mut self.options.year)
                                 .with_label("Year")
-                                .with_options(Options::get_valid_years().map(Some))
+                                .with_options(self.catalog.get_years().map(Some))
                                 .show(ui, |state| match state {
                                     Some(selected) => selected.to_string(),
                                     None => "Select".into(),
                                 });
 
+                            // Clear the round if the new year doesn't have it.
+                            if year_response.is_some_and(|response| response.changed())
+                                && self.catalog.get_path(&self.options).is_none()
+                            {
+                                self.options.round = None;
+                            }
+
                             // Round selection
                             Dropdown::with_state(&mut self.options.round)
                                 .with_label("Round")
-                                .with_options(
-                                    Options::get_valid_rounds(self.options.year)
-                                        .unwrap_or(1..=1)
-                                        .map(Some),
+                                .with_options(self.catalog.get_rounds(self.options.year).map(Some))
+                                .with_enabled(
+                                    self.options.year.is_some() && !self.options.all_rounds,
                                 )
-                                .with_enabled(self.options.year.is_some())
                                 .show(ui, |state| match state {
                                     Some(selected) => selected.to_string(),
                                     None => "Select".into(),
                                 });
 
-                            // Load button
-                            if ui
-                                .add_enabled(
-                                    self.options.is_complete(),
-                                    egui::Button::new("Load dataset"),
-                                )
+                            // Compare all rounds of the year
+                            ui.checkbox(&mut self.options.all_rounds, "All rounds");
+
+                            // Load button, or the progress of the dataset being loaded
+                            let paths = self.catalog.get_paths(&self.options);
+
+                            if let Some(loader) = &self.loader {
+                                ui.spinner();
+                                ui.add(
+                                    egui::ProgressBar::new(loader.get_progress())
+                                        .desired_width(160.0)
+                                        .show_percentage(),
+                                );
+
+                                // Dropping the loader cancels it.
+                                if ui.button("Cancel").clicked() {
+                                    self.loader = None;
+                                }
+                            } else if ui
+                                .add_enabled(!paths.is_empty(), egui::Button::new("Load dataset"))
                                 .clicked()
+                                && !(self.dataset.is_loaded()
+                                    && self.dataset.get_options() == &self.options)
                             {
-                                self.dataset.load(&self.options).unwrap();
-                                self.dataset.sort(&self.sort);
+                                let ctx = ui.ctx().clone();
+
+                                self.loader = Some(Loader::spawn(
+                                    self.options,
+                                    paths
+                                        .iter()
+                                        .map(|(round, path)| (*round, path.to_path_buf()))
+                                        .collect(),
+                                    self.branch_families.clone(),
+                                    move || ctx.request_repaint(),
+                                ));
+                            }
+
+                            // Files which were skipped while scanning
+                            let rejected = self.catalog.get_rejected();
+
+                            if !rejected.is_empty() {
+                                ui.label(format!("{} invalid dataset(s) skipped.", rejected.len()))
+                                    .on_hover_ui(|ui| {
+                                        for path in rejected {
+                                            ui.label(path.display().to_string());
+                                        }
+                                    });
                             }
                         });
                     });
@@ -63,112 +283,573 @@ impl eframe::App for AppState {
                 // Institute filter
                 ui.add_enabled_ui(self.dataset.is_loaded(), |ui| {
                     ui.collapsing("Institute", |ui| {
-                        let filters = self.dataset.get_filters();
-
-                        for (label, (enabled, institutes)) in filters.institute_kinds.iter_mut() {
-                            egui::collapsing_header::CollapsingState::load_with_default_open(
-                                ui.ctx(),
-                                ui.make_persistent_id(format!("kind_{label}")),
-                                false,
-                            )
-                            .show_header(ui, |ui| {
-                                ui.checkbox(enabled, label);
+                        let (filters, dictionaries) = self.dataset.get_filters_with_dictionaries();
+                        let institutes = dictionaries.institute.values();
+
+                        let changed = TreeSelect::with_state(
+                            &mut filters.institute,
+                            institutes,
+                            dictionaries.institute_kind.values(),
+                            &dictionaries.kind_of,
+                        )
+                        .with_id_salt("Institute")
+                        .with_hover_text(|institute| {
+                            find_institute(&institutes[institute]).map(|institute| {
+                                format!(
+                                    "{}, {}, {}",
+                                    institute.short_name, institute.city, institute.state
+                                )
                             })
-                            .body_unindented(|ui| {
-                                ui.horizontal_wrapped(|ui| {
-                                    for (value, checked) in institutes.iter_mut() {
-                                        ui.checkbox(checked, value).clicked();
-                                    }
-                                });
-                            });
+                        })
+                        .show(ui)
+                        .changed();
+
+                        // Types are checked through their institutes.
+                        if changed {
+                            filters.select_institute_kinds(dictionaries);
+                            filters_changed = true;
                         }
                     });
                 });
 
                 // Filters
                 ui.add_enabled_ui(self.dataset.is_loaded(), |ui| {
-                    let filters = self.dataset.get_filters();
+                    let (filters, dictionaries) = self.dataset.get_filters_with_dictionaries();
 
                     ui.collapsing("Filters", |ui| {
                         // Branch
-                        Multiselect::with_state(&mut filters.branch)
-                            .with_label("Branch")
-                            .with_scroll()
-                            .show(ui);
+                        filters_changed |= Multiselect::with_state(
+                            &mut filters.branch,
+                            dictionaries.branch.values(),
+                        )
+                        .with_label("Branch")
+                        .with_search()
+                        .with_alphabetical_order()
+                        .with_groups(dictionaries.family.values(), &dictionaries.family_of)
+                        .with_scroll()
+                        .show(ui)
+                        .is_some_and(|response| response.changed());
+
+                        // Duration
+                        filters_changed |= Multiselect::with_state(
+                            &mut filters.duration,
+                            dictionaries.duration.values(),
+                        )
+                        .with_label("Duration")
+                        .show(ui)
+                        .is_some_and(|response| response.changed());
+
+                        // Degree
+                        filters_changed |= Multiselect::with_state(
+                            &mut filters.degree,
+                            dictionaries.degree.values(),
+                        )
+                        .with_label("Degree")
+                        .show(ui)
+                        .is_some_and(|response| response.changed());
 
                         // Quota
-                        Multiselect::with_state(&mut filters.quota)
-                            .with_label("Quota")
-                            .show(ui);
+                        filters_changed |= Multiselect::with_state(
+                            &mut filters.quota,
+                            dictionaries.quota.values(),
+                        )
+                        .with_label("Quota")
+                        .with_alphabetical_order()
+                        .show(ui)
+                        .is_some_and(|response| response.changed());
 
                         // Seat type
-                        Multiselect::with_state(&mut filters.seat_type)
-                            .with_label("Seat type")
-                            .show(ui);
+                        filters_changed |= Multiselect::with_state(
+                            &mut filters.seat_type,
+                            dictionaries.seat_type.values(),
+                        )
+                        .with_label("Seat type")
+                        .with_alphabetical_order()
+                        .show(ui)
+                        .is_some_and(|response| response.changed());
 
                         // Gender
-                        Multiselect::with_state(&mut filters.gender)
-                            .with_label("Gender")
-                            .show(ui);
-
-                        // Opening rank
-                        RangeSelector::with_state(&mut filters.or, &filters.or_bounds)
-                            .with_label("Opening rank")
-                            .ui(ui);
-
-                        // Closing rank
-                        RangeSelector::with_state(&mut filters.cr, &filters.cr_bounds)
-                            .with_label("Closing rank")
-                            .ui(ui);
+                        filters_changed |= Multiselect::with_state(
+                            &mut filters.gender,
+                            dictionaries.gender.values(),
+                        )
+                        .with_label("Gender")
+                        .show(ui)
+                        .is_some_and(|response| response.changed());
+
+                        // Quotas the candidate is eligible for, from their home state
+                        ui.horizontal(|ui| {
+                            Dropdown::with_state(&mut self.candidate.home_state)
+                                .with_label("Home state")
+                                .with_search()
+                                .with_options(
+                                    std::iter::once(None)
+                                        .chain(STATES.iter().map(|state| Some(state.to_string()))),
+                                )
+                                .show(ui, |state| match state {
+                                    Some(state) => state.clone(),
+                                    None => "Select".into(),
+                                });
+
+                            filters_changed |= ui
+                                .checkbox(&mut filters.eligible_quotas, "Eligible quotas only")
+                                .on_hover_text(
+                                    "Only show home state seats at institutes in your home state, \
+                                     other state seats elsewhere, and the Goa, Jammu and Kashmir, \
+                                     Ladakh and Andhra Pradesh quotas if you are from there.",
+                                )
+                                .changed();
+                        });
+
+                        // Ranks for each exam, if the dataset has institutes using it
+                        for (domain, ranks) in [
+                            (RankDomain::Advanced, &mut filters.advanced),
+                            (RankDomain::Main, &mut filters.main),
+                        ] {
+                            if ranks.is_empty() {
+                                continue;
+                            }
+
+                            let has_preparatory = ranks.has_preparatory;
+
+                            // Opening rank
+                            let mut selector =
+                                RangeSelector::with_state(&mut ranks.or, &ranks.or_bounds)
+                                    .with_label(format!("Opening rank ({domain})"));
+
+                            if has_preparatory {
+                                selector = selector.with_preparatory(&mut ranks.or_preparatory);
+                            }
+
+                            filters_changed |= selector.ui(ui).changed();
+
+                            // Closing rank
+                            let mut selector =
+                                RangeSelector::with_state(&mut ranks.cr, &ranks.cr_bounds)
+                                    .with_label(format!("Closing rank ({domain})"));
+
+                            if has_preparatory {
+                                selector = selector.with_preparatory(&mut ranks.cr_preparatory);
+                            }
+
+                            filters_changed |= selector.ui(ui).changed();
+                        }
                     });
                 });
 
-                // Sort selection
+                // The home state is shared with the predictor, and new datasets start without it.
+                if self.dataset.is_loaded()
+                    && self.dataset.get_filters().get_home_state()
+                        != self.candidate.home_state.as_deref()
+                {
+                    let (filters, dictionaries) = self.dataset.get_filters_with_dictionaries();
+
+                    filters.set_home_state(dictionaries, self.candidate.home_state.as_deref());
+                    filters_changed |= filters.eligible_quotas;
+                }
+
+                // Only filter entries again when something changed.
+                if filters_changed {
+                    self.apply_filters();
+                }
+
+                // Predictor
+                ui.add_enabled_ui(self.dataset.is_loaded(), |ui| {
+                    let candidate = &mut self.candidate;
+
+                    ui.collapsing("Predictor", |ui| {
+                        ui.checkbox(
+                            &mut self.predicting,
+                            "Only show rows I could get, with my chances",
+                        );
+
+                        // JEE Main ranks, used by every institute except the IITs
+                        ui.horizontal(|ui| {
+                            ui.label("JEE Main:");
+                            rank_input(ui, "CRL", &mut candidate.main.crl);
+                            rank_input(ui, "Category", &mut candidate.main.category);
+                            rank_input(ui, "CRL PwD", &mut candidate.main.crl_pwd);
+                            rank_input(ui, "Category PwD", &mut candidate.main.category_pwd);
+                        });
+
+                        // JEE Advanced ranks, used by the IITs
+                        ui.horizontal(|ui| {
+                            ui.label("JEE Advanced:");
+                            rank_input(ui, "CRL", &mut candidate.advanced.crl);
+                            rank_input(ui, "Category", &mut candidate.advanced.category);
+                            rank_input(ui, "CRL PwD", &mut candidate.advanced.crl_pwd);
+                            rank_input(ui, "Category PwD", &mut candidate.advanced.category_pwd);
+                        });
+
+                        ui.horizontal(|ui| {
+                            Dropdown::with_state(&mut candidate.category)
+                                .with_label("Category")
+                                .with_options(Category::as_vec().into_iter())
+                                .show(ui, Category::to_string);
+
+                            Dropdown::with_state(&mut candidate.gender)
+                                .with_label("Gender")
+                                .with_options(Gender::as_vec().into_iter())
+                                .show(ui, Gender::to_string);
+
+                            ui.label(format!(
+                                "Home state: {}",
+                                candidate.home_state.as_deref().unwrap_or("not set")
+                            ))
+                            .on_hover_text("The home state is set under Filters.");
+
+                            ui.checkbox(&mut candidate.pwd, "PwD");
+                        });
+                    });
+                });
+
+                // SQL console
+                ui.add_enabled_ui(self.dataset.is_loaded(), |ui| {
+                    ui.collapsing("SQL", |ui| {
+                        ui.label(
+                            "Run a SELECT against the `data` and `institutes` tables. \
+                             Attached datasets are available as `<alias>.data`.",
+                        );
+
+                        let editor = ui.add(
+                            egui::TextEdit::multiline(&mut self.sql)
+                                .code_editor()
+                                .desired_rows(3)
+                                .desired_width(f32::INFINITY)
+                                .hint_text("SELECT * FROM data LIMIT 10;"),
+                        );
+
+                        // Ctrl+Enter runs the query from the editor.
+                        let shortcut = editor.has_focus()
+                            && ui.input(|i| i.modifiers.command && i.key_pressed(egui::Key::Enter));
+
+                        ui.horizontal(|ui| {
+                            if ui.button("Run").clicked() || shortcut {
+                                self.query_result = Some(self.dataset.query(&self.sql));
+                            }
+
+                            if ui
+                                .add_enabled(
+                                    self.query_result.is_some(),
+                                    egui::Button::new("Clear results"),
+                                )
+                                .clicked()
+                            {
+                                self.query_result = None;
+                            }
+
+                            ui.separator();
+
+                            // Attach another dataset
+                            Dropdown::with_state(&mut self.attach_options.year)
+                                .with_label("Attach year")
+                                .with_options(self.catalog.get_years().map(Some))
+                                .show(ui, |state| match state {
+                                    Some(selected) => selected.to_string(),
+                                    None => "Select".into(),
+                                });
+
+                            Dropdown::with_state(&mut self.attach_options.round)
+                                .with_label("Attach round")
+                                .with_options(
+                                    self.catalog.get_rounds(self.attach_options.year).map(Some),
+                                )
+                                .with_enabled(self.attach_options.year.is_some())
+                                .show(ui, |state| match state {
+                                    Some(selected) => selected.to_string(),
+                                    None => "Select".into(),
+                                });
+
+                            let path = self.catalog.get_path(&self.attach_options);
+
+                            if ui
+                                .add_enabled(path.is_some(), egui::Button::new("Attach"))
+                                .clicked()
+                                && let Some(path) = path
+                                && let Err(error) = self.dataset.attach(&self.attach_options, path)
+                            {
+                                self.query_result = Some(Err(error));
+                            }
+
+                            let attached = self.dataset.get_attached();
+
+                            if !attached.is_empty() {
+                                ui.label(format!("Attached: {}", attached.join(", ")));
+                            }
+                        });
+
+                        // Errors are shown inline, below the editor.
+                        if let Some(Err(error)) = &self.query_result {
+                            ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+                        }
+                    });
+                });
+
+                // Sorting is chosen from the table headers.
                 ui.horizontal(|ui| {
                     ui.add_enabled_ui(self.dataset.is_loaded(), |ui| {
-                        if let Some(response) = Dropdown::with_state(&mut self.sort)
-                            .with_label("Sort by")
-                            .with_options(Sort::as_vec().into_iter())
-                            .show(ui, Sort::to_string)
+                        if ui
+                            .checkbox(&mut self.group_by_family, "Group by family")
+                            .changed()
                         {
-                            if response.changed() {
-                                self.dataset.sort(&self.sort);
-                            }
+                            self.sort();
+                        }
+
+                        ui.separator();
+
+                        // Export the rows in the table, as they are filtered and sorted
+                        Dropdown::with_state(&mut self.export_format)
+                            .with_label("Export as")
+                            .with_options(ExportFormat::as_vec().into_iter())
+                            .show(ui, ExportFormat::to_string);
+
+                        if ui.button("Export…").clicked() {
+                            self.export();
                         }
                     })
                 });
             });
 
+        // Trend Panel
+        if let Some(trend) = &self.trend {
+            let mut close = false;
+
+            egui::TopBottomPanel::bottom("Trend Panel")
+                .resizable(true)
+                .default_height(280.0)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        if ui.button("Close").clicked() {
+                            close = true;
+                        }
+
+                        let key = &trend.key;
+
+                        ui.strong(format!("{}, {}", key.institute, key.branch));
+                        ui.label(format!(
+                            "{} / {} / {}",
+                            key.quota, key.seat_type, key.gender
+                        ));
+                    });
+
+                    // Years missing the program are drawn as breaks in the lines.
+                    let segments = trend.get_segments();
+
+                    Plot::new("Trend Plot")
+                        .legend(Legend::default())
+                        .x_axis_label("Year")
+                        .y_axis_label("Rank (final round)")
+                        .show(ui, |plot_ui| {
+                            for segment in &segments {
+                                // Preparatory ranks aren't on the same scale, so they aren't drawn.
+                                let or = || {
+                                    segment.iter().filter_map(|(year, r)| {
+                                        Some([*year as f64, r.or.get_regular()? as f64])
+                                    })
+                                };
+                                let cr = || {
+                                    segment.iter().filter_map(|(year, r)| {
+                                        Some([*year as f64, r.cr.get_regular()? as f64])
+                                    })
+                                };
+
+                                plot_ui.line(
+                                    Line::new(PlotPoints::from_iter(or()))
+                                        .name("Opening rank")
+                                        .color(OPENING_COLOR),
+                                );
+                                plot_ui.points(
+                                    Points::new(PlotPoints::from_iter(or()))
+                                        .name("Opening rank")
+                                        .color(OPENING_COLOR)
+                                        .radius(3.0),
+                                );
+                                plot_ui.line(
+                                    Line::new(PlotPoints::from_iter(cr()))
+                                        .name("Closing rank")
+                                        .color(CLOSING_COLOR),
+                                );
+                                plot_ui.points(
+                                    Points::new(PlotPoints::from_iter(cr()))
+                                        .name("Closing rank")
+                                        .color(CLOSING_COLOR)
+                                        .radius(3.0),
+                                );
+                            }
+                        });
+                });
+
+            if close {
+                self.trend = None;
+            }
+        }
+
         // Center Panel
+        let mut clicked: Option<EntryKey> = None;
+        let mut sorted = false;
+
         egui::CentralPanel::default().show(ctx, |ui| {
+            // Table view of query results
+            if let Some(Ok(result)) = &self.query_result {
+                if result.truncated {
+                    ui.label(format!("Showing the first {} rows.", result.rows.len()));
+                } else {
+                    ui.label(format!("{} rows.", result.rows.len()));
+                }
+
+                TableBuilder::new(ui)
+                    .columns(Column::auto().resizable(true), result.columns.len())
+                    .striped(true)
+                    .header(24.0, |mut header| {
+                        for column in &result.columns {
+                            header.col(label(column));
+                        }
+                    })
+                    .body(|body| {
+                        body.rows(30.0, result.rows.len(), |mut row| {
+                            for value in &result.rows[row.index()] {
+                                row.col(label(value));
+                            }
+                        });
+                    });
+            }
             // Table view of dataset
-            if self.dataset.is_loaded() {
+            else if self.dataset.is_loaded() {
+                // Search bar
+                ui.horizontal(|ui| {
+                    ui.label("Search");
+
+                    let mut changed = ui
+                        .add(
+                            egui::TextEdit::singleline(&mut self.search)
+                                .hint_text(
+                                    "Institute, branch, quota or seat type, such as iitb cse",
+                                )
+                                .desired_width(360.0),
+                        )
+                        .changed();
+
+                    if !self.search.is_empty() && ui.button("Clear").clicked() {
+                        self.search.clear();
+                        changed = true;
+                    }
+
+                    if changed {
+                        let (filters, dictionaries) = self.dataset.get_filters_with_dictionaries();
+                        filters.set_search(dictionaries, &self.search);
+                        self.apply_filters();
+                    }
+                });
+
+                if self.predicting {
+                    self.predict();
+                }
+
+                let rounds = self.dataset.get_rounds();
+                let dictionaries = self.dataset.get_dictionaries();
+                let search = self.dataset.get_filters().get_search();
+
+                // Rounds are shown side by side when more than one is loaded.
+                let side_by_side = rounds.len() > 1;
+                let predictions: &[Prediction] = match &self.predictions {
+                    Some((_, predictions)) if self.predicting => predictions,
+                    _ => &[],
+                };
+                // The institute and branch take up the remaining width.
+                let columns = get_table_columns(rounds, self.predicting);
+
                 TableBuilder::new(ui)
                     .columns(Column::remainder(), 2)
-                    .columns(Column::auto(), 5)
+                    .columns(Column::auto(), columns.len() - 2)
                     .striped(true)
+                    .sense(egui::Sense::click())
                     .header(24.0, |mut header| {
-                        header.col(label("Institute"));
-                        header.col(label("Branch"));
-                        header.col(label("Quota"));
-                        header.col(label("Seat type"));
-                        header.col(label("Gender"));
-                        header.col(label("Opening Rank"));
-                        header.col(label("Closing Rank"));
+                        let sort = &mut self.sort;
+
+                        for (text, column) in columns {
+                            header.col(sort_header(text, column, sort, &mut sorted));
+                        }
                     })
                     .body(|body| {
-                        let entries: Vec<&Entry> = self.dataset.get_entries().collect();
-
-                        body.rows(30.0, entries.len(), |mut row| {
-                            let data = &entries[row.index()];
-
-                            row.col(label(&data.institute));
-                            row.col(label(&data.branch));
-                            row.col(label(&data.quota));
-                            row.col(label(&data.seat_type));
-                            row.col(label(&data.gender));
-                            row.col(label(data.or));
-                            row.col(label(data.cr));
+                        let count = if self.predicting {
+                            predictions.len()
+                        } else {
+                            self.dataset.get_entry_count()
+                        };
+
+                        body.rows(30.0, count, |mut row| {
+                            let data: &Entry = if self.predicting {
+                                self.dataset
+                                    .get_entry(predictions[row.index()].index)
+                                    .unwrap()
+                            } else {
+                                self.dataset.get_entry(row.index()).unwrap()
+                            };
+
+                            row.col(highlighted_label(
+                                dictionaries.institute.get(data.institute),
+                                search,
+                                SearchField::Institute,
+                            ));
+                            row.col(highlighted_label(
+                                dictionaries.branch.get(data.branch),
+                                search,
+                                SearchField::Branch,
+                            ));
+                            row.col(label(dictionaries.family.get(data.family)));
+                            row.col(highlighted_label(
+                                dictionaries.quota.get(data.quota),
+                                search,
+                                SearchField::Quota,
+                            ));
+                            row.col(highlighted_label(
+                                dictionaries.seat_type.get(data.seat_type),
+                                search,
+                                SearchField::SeatType,
+                            ));
+                            row.col(label(dictionaries.gender.get(data.gender)));
+                            row.col(label(data.domain));
+
+                            if side_by_side {
+                                for ranks in &data.rounds {
+                                    match ranks {
+                                        Some(ranks) => {
+                                            row.col(rank_label(ranks.or));
+                                            row.col(rank_label(ranks.cr));
+                                        }
+
+                                        // Not offered in this round
+                                        None => {
+                                            row.col(label("—"));
+                                            row.col(label("—"));
+                                        }
+                                    }
+                                }
+                            } else {
+                                row.col(rank_label(data.or));
+                                row.col(rank_label(data.cr));
+                            }
+
+                            if let Some(prediction) = predictions.get(row.index()) {
+                                row.col(label(prediction.rank));
+                                row.col(|ui| {
+                                    ui.horizontal_centered(|ui| {
+                                        ui.colored_label(
+                                            chance_color(prediction.chance),
+                                            prediction.chance.to_string(),
+                                        );
+                                    });
+                                });
+                            }
+
+                            // Show the trend of the clicked row
+                            if row
+                                .response()
+                                .on_hover_text("Click to show trend over the years")
+                                .clicked()
+                            {
+                                clicked = Some(dictionaries.get_key(data));
+                            }
                         });
                     });
             }
@@ -179,9 +860,81 @@ impl eframe::App for AppState {
                 });
             }
         });
+
+        if sorted {
+            self.sort();
+        }
+
+        if let Some(key) = clicked {
+            match Trend::load(&self.catalog, key) {
+                Ok(trend) => self.trend = Some(trend),
+                Err(error) => self.error = Some(error),
+            }
+
+            ctx.request_repaint();
+        }
     }
 }
 
+/// An input for a rank which may not apply, enabled by a checkbox.
+fn rank_input(ui: &mut egui::Ui, text: &str, rank: &mut Option<u32>) {
+    let mut enabled = rank.is_some();
+    let mut value = rank.unwrap_or(1);
+
+    ui.checkbox(&mut enabled, text);
+    ui.add_enabled(
+        enabled,
+        egui::DragValue::new(&mut value).range(1..=10_000_000),
+    );
+
+    *rank = enabled.then_some(value);
+}
+
+/// Get the header and sort column of every column of the dataset table, in order.
+/// Rows must add a cell for each of them.
+fn get_table_columns(rounds: &[u8], predicting: bool) -> Vec<(String, SortColumn)> {
+    let mut columns: Vec<(String, SortColumn)> = [
+        ("Institute", SortColumn::Institute),
+        ("Branch", SortColumn::Branch),
+        ("Family", SortColumn::Family),
+        ("Quota", SortColumn::Quota),
+        ("Seat type", SortColumn::SeatType),
+        ("Gender", SortColumn::Gender),
+        ("Exam", SortColumn::Exam),
+    ]
+    .map(|(text, column)| (text.to_string(), column))
+    .into();
+
+    // Rounds are shown side by side when more than one is loaded.
+    if rounds.len() > 1 {
+        for (i, round) in rounds.iter().enumerate() {
+            columns.push((format!("OR (R{round})"), SortColumn::RoundOpening(i)));
+            columns.push((format!("CR (R{round})"), SortColumn::RoundClosing(i)));
+        }
+    } else {
+        columns.push(("Opening Rank".into(), SortColumn::Opening));
+        columns.push(("Closing Rank".into(), SortColumn::Closing));
+    }
+
+    if predicting {
+        columns.push(("Your rank".into(), SortColumn::CandidateRank));
+        columns.push(("Chance".into(), SortColumn::Chance));
+    }
+
+    columns
+}
+
+fn chance_color(chance: Chance) -> egui::Color32 {
+    match chance {
+        Chance::Safe => egui::Color32::from_rgb(80, 200, 120),
+        Chance::Moderate => egui::Color32::from_rgb(230, 190, 60),
+        Chance::Reach => egui::Color32::from_rgb(240, 100, 90),
+    }
+}
+
+const OPENING_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 160, 255);
+const CLOSING_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 150, 60);
+
 fn label<T: ToString>(content: T) -> impl FnOnce(&mut egui::Ui) {
     move |ui| {
         ui.horizontal_centered(|ui| {
@@ -189,3 +942,181 @@ fn label<T: ToString>(content: T) -> impl FnOnce(&mut egui::Ui) {
         });
     }
 }
+
+/// A table header which sorts by `column` when clicked, or adds it as another
+/// key when shift-clicked, setting `sorted`. Sorted columns show their
+/// direction, and their position if there are several keys.
+fn sort_header<'a, T: ToString + 'a>(
+    content: T,
+    column: SortColumn,
+    sort: &'a mut Sort,
+    sorted: &'a mut bool,
+) -> impl FnOnce(&mut egui::Ui) + 'a {
+    move |ui| {
+        ui.horizontal_centered(|ui| {
+            let mut text = content.to_string();
+
+            if let Some((position, direction)) = sort.get_key(column) {
+                text.push(' ');
+                text.push_str(direction.get_arrow());
+
+                if sort.get_keys().len() > 1 {
+                    text.push_str(&(position + 1).to_string());
+                }
+            }
+
+            let response = ui
+                .add(egui::Label::new(text).sense(egui::Sense::click()))
+                .on_hover_text("Click to sort, shift-click to sort by more columns");
+
+            if response.clicked() {
+                if ui.input(|input| input.modifiers.shift) {
+                    sort.add(column);
+                } else {
+                    sort.select(column);
+                }
+
+                *sorted = true;
+            }
+        });
+    }
+}
+
+/// Like [`label`], but highlights the parts of `text` matching `search`.
+fn highlighted_label<'a>(
+    text: &'a str,
+    search: &'a Search,
+    field: SearchField,
+) -> impl FnOnce(&mut egui::Ui) + 'a {
+    move |ui| {
+        ui.horizontal_centered(|ui| {
+            let highlights = search.get_highlights(field, text);
+
+            if highlights.is_empty() {
+                ui.label(text);
+                return;
+            }
+
+            let normal = egui::TextFormat {
+                font_id: egui::TextStyle::Body.resolve(ui.style()),
+                color: ui.visuals().text_color(),
+                ..Default::default()
+            };
+            let highlighted = egui::TextFormat {
+                background: ui.visuals().selection.bg_fill,
+                color: ui.visuals().strong_text_color(),
+                ..normal.clone()
+            };
+
+            let mut job = egui::text::LayoutJob::default();
+            let mut start = 0;
+
+            for range in highlights {
+                job.append(&text[start..range.start], 0.0, normal.clone());
+                job.append(&text[range.clone()], 0.0, highlighted.clone());
+                start = range.end;
+            }
+
+            job.append(&text[start..], 0.0, normal);
+            ui.label(job);
+        });
+    }
+}
+
+/// Like [`label`], but explains preparatory ranks when hovered.
+fn rank_label(rank: Rank) -> impl FnOnce(&mut egui::Ui) {
+    move |ui| {
+        ui.horizontal_centered(|ui| {
+            let response = ui.label(rank.to_string());
+
+            if rank.is_preparatory() {
+                response
+                    .on_hover_text("Preparatory rank of a PwD candidate, after every regular rank");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eframe::App;
+
+    /// Run a frame of the whole app.
+    fn run(app: &mut AppState, ctx: &egui::Context) {
+        let input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(1600.0, 900.0),
+            )),
+            ..Default::default()
+        };
+
+        let _ = ctx.run(input, |ctx| {
+            app.update(ctx, &mut eframe::Frame::_new_kittest());
+        });
+    }
+
+    #[test]
+    fn table_columns() {
+        let names = |rounds: &[u8], predicting: bool| -> Vec<String> {
+            get_table_columns(rounds, predicting)
+                .into_iter()
+                .map(|(text, _)| text)
+                .collect()
+        };
+
+        // Text columns, including the family, and then the ranks
+        assert_eq!(
+            names(&[5], false),
+            [
+                "Institute",
+                "Branch",
+                "Family",
+                "Quota",
+                "Seat type",
+                "Gender",
+                "Exam",
+                "Opening Rank",
+                "Closing Rank"
+            ]
+        );
+
+        // No more columns than headers when rounds are side by side
+        let columns = names(&[1, 2, 3], true);
+
+        assert_eq!(columns.len(), 7 + 3 * 2 + 2);
+        assert_eq!(columns[7..9], ["OR (R1)", "CR (R1)"]);
+        assert_eq!(columns[13..], ["Your rank", "Chance"]);
+    }
+
+    #[test]
+    fn table_renders_every_mode() {
+        let ctx = egui::Context::default();
+        let mut app = AppState::default();
+
+        app.candidate.main.crl = Some(10_000);
+        app.candidate.advanced.crl = Some(5_000);
+
+        let single = Options::new(2024, 5);
+        let multiple = Options {
+            year: Some(2024),
+            round: None,
+            all_rounds: true,
+        };
+
+        // Every header and cell needs a column, or egui_extras panics in debug builds.
+        for options in [single, multiple] {
+            let paths = app.catalog.get_paths(&options);
+            app.dataset.load(&options, &paths).unwrap();
+            app.sort();
+
+            for predicting in [false, true] {
+                app.predicting = predicting;
+                run(&mut app, &ctx);
+            }
+
+            assert!(!app.predictions.as_ref().unwrap().1.is_empty());
+        }
+    }
+}
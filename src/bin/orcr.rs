@@ -0,0 +1,818 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use josaa_orcr::{
+    BranchFamilies, Catalog, DEFAULT_ROOT, Dataset, Dictionaries, Dictionary, Entry, ExportFormat,
+    HeaderMapping, Import, ImportField, Options, STATES, Sort, SortKey, export, find_institute,
+    format_duration, normalize_name, read_csv, read_html,
+    types::{BitSet, RankRange},
+};
+use std::{path::PathBuf, process::ExitCode};
+
+/// At least one row was printed.
+const EXIT_SUCCESS: u8 = 0;
+/// The dataset loaded, but no rows matched the filters.
+const EXIT_NO_MATCHES: u8 = 1;
+/// The arguments are invalid, such as a filter value which doesn't exist.
+/// Also used by clap for malformed arguments.
+const EXIT_USAGE: u8 = 2;
+/// The dataset, or a file being imported, couldn't be found or read.
+const EXIT_DATASET: u8 = 3;
+/// The output couldn't be written.
+const EXIT_OUTPUT: u8 = 4;
+
+/// Query JoSAA opening and closing ranks from the command line.
+#[derive(Parser)]
+#[command(name = "orcr", version)]
+struct Cli {
+    /// Extra directories to look for datasets in, after the default ones,
+    /// with the `<root>/<year>/data-<year>-<round>.db` layout.
+    #[arg(long = "db", global = true)]
+    roots: Vec<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the available years and rounds.
+    List {
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+
+    /// Print the rows of a dataset which match the filters.
+    Show(Box<ShowArgs>),
+
+    /// Import OR-CR result pages saved from the JoSAA website, or CSV files, as a dataset.
+    Import(ImportArgs),
+}
+
+#[derive(Args)]
+struct ShowArgs {
+    #[arg(long)]
+    year: u16,
+
+    /// The round to show. Defaults to the final round of the year.
+    #[arg(long, conflicts_with = "all_rounds")]
+    round: Option<u8>,
+
+    /// Show the ranks of every round of the year side by side.
+    #[arg(long)]
+    all_rounds: bool,
+
+    /// Only show branches containing this text. Can be repeated.
+    #[arg(long)]
+    branch: Vec<String>,
+
+    /// Only show branches whose family contains this text, such as `Computer`. Can be repeated.
+    #[arg(long)]
+    family: Vec<String>,
+
+    /// Read the families of branches from this file instead of the bundled ones,
+    /// as tab-separated `family` and `keyword` columns.
+    #[arg(long)]
+    branch_families: Option<PathBuf>,
+
+    /// Only show programs of this duration in years, such as `5`. Can be repeated.
+    #[arg(long)]
+    duration: Vec<u8>,
+
+    /// Only show degrees containing this text, such as `Dual`. Can be repeated.
+    #[arg(long)]
+    degree: Vec<String>,
+
+    /// Only show institutes containing this text, or with this short name
+    /// such as `IIT BBS`. Can be repeated.
+    #[arg(long)]
+    institute: Vec<String>,
+
+    /// Only show institutes whose type contains this text. Can be repeated.
+    #[arg(long)]
+    institute_type: Vec<String>,
+
+    /// Only show this quota, such as `AI`. Can be repeated.
+    #[arg(long)]
+    quota: Vec<String>,
+
+    /// Only show this seat type, such as `OPEN`. Can be repeated.
+    #[arg(long)]
+    seat_type: Vec<String>,
+
+    /// Only show this gender, such as `Gender-Neutral`. Can be repeated.
+    #[arg(long)]
+    gender: Vec<String>,
+
+    /// Only show opening ranks in this range, such as `1..5000`, `..5000` or `1000..`.
+    #[arg(long = "or", value_parser = parse_range)]
+    opening: Option<RankRange>,
+
+    /// Only show closing ranks in this range, such as `1..5000`, `..5000` or `1000..`.
+    #[arg(long = "cr", value_parser = parse_range)]
+    closing: Option<RankRange>,
+
+    /// Hide rows with preparatory ranks, such as `123P`, which are
+    /// otherwise shown regardless of `--or` and `--cr`.
+    #[arg(long)]
+    no_preparatory: bool,
+
+    /// Only show quotas a candidate from this state is eligible for, such as
+    /// home state seats at its institutes and other state seats elsewhere.
+    #[arg(long, value_parser = parse_state)]
+    home_state: Option<String>,
+
+    /// Only show rows matching every word of this search in their institute, branch,
+    /// quota or seat type, such as `"iitb cse"`. Abbreviations and short names match too.
+    #[arg(long)]
+    search: Option<String>,
+
+    /// Sort by this column, such as `cr`, `institute` or `or-desc`. Can be
+    /// repeated to break ties by more columns.
+    #[arg(long, default_value = "cr")]
+    sort: Vec<SortKey>,
+
+    /// Print at most this many rows.
+    #[arg(long)]
+    limit: Option<usize>,
+
+    #[arg(long, value_enum, default_value_t = ShowFormat::Table)]
+    format: ShowFormat,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    #[arg(long)]
+    year: u16,
+
+    #[arg(long)]
+    round: u8,
+
+    /// The directory to write the dataset into, with the
+    /// `<root>/<year>/data-<year>-<round>.db` layout.
+    #[arg(long, default_value = DEFAULT_ROOT)]
+    into: PathBuf,
+
+    /// Replace the dataset if it already exists.
+    #[arg(long)]
+    force: bool,
+
+    /// Read a field of CSV files from the column with this header, such as
+    /// `or="Opening Rank"`. Can be repeated.
+    #[arg(long = "column", value_name = "FIELD=HEADER", value_parser = parse_column)]
+    columns: Vec<(ImportField, String)>,
+
+    /// The files to import, such as a saved page for each institute type.
+    /// Files ending in `.csv` are read as CSV, and others as HTML.
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum Format {
+    /// Aligned columns, for reading.
+    Table,
+    /// Tab-separated values with a header row, for scripts.
+    Tsv,
+}
+
+/// The formats rows can be shown in, which include every [`ExportFormat`].
+#[derive(ValueEnum, Clone, Copy)]
+enum ShowFormat {
+    /// Aligned columns, for reading.
+    Table,
+    /// Tab-separated values with a header row, for scripts.
+    Tsv,
+    Csv,
+    /// An array of objects named like the fields of an entry.
+    Json,
+    Markdown,
+}
+
+/// An error which ends the program, with its exit code.
+struct Failure {
+    code: u8,
+    message: String,
+}
+
+impl Failure {
+    fn usage(message: impl Into<String>) -> Self {
+        Self {
+            code: EXIT_USAGE,
+            message: message.into(),
+        }
+    }
+
+    fn dataset(error: josaa_orcr::Error) -> Self {
+        Self {
+            code: EXIT_DATASET,
+            message: error.to_string(),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let catalog = open_catalog(cli.roots);
+
+    let result = match cli.command {
+        Command::List { format } => list(&catalog, format),
+        Command::Show(args) => show(&catalog, &args),
+        Command::Import(args) => import(&args),
+    };
+
+    match result {
+        Ok(code) => ExitCode::from(code),
+        Err(failure) => {
+            eprintln!("orcr: {}", failure.message);
+            ExitCode::from(failure.code)
+        }
+    }
+}
+
+/// Open a catalog over the default roots, followed by `extra_roots`.
+fn open_catalog(extra_roots: Vec<PathBuf>) -> Catalog {
+    let mut roots = Catalog::get_default_roots();
+    roots.extend(extra_roots);
+
+    Catalog::with_roots(roots)
+}
+
+fn list(catalog: &Catalog, format: Format) -> Result<u8, Failure> {
+    let rows: Vec<Vec<String>> = catalog
+        .get_years()
+        .map(|year| {
+            let rounds: Vec<String> = catalog
+                .get_rounds(Some(year))
+                .map(|round| round.to_string())
+                .collect();
+
+            vec![year.to_string(), rounds.join(" ")]
+        })
+        .collect();
+
+    for path in catalog.get_rejected() {
+        eprintln!("orcr: skipped invalid dataset {}", path.display());
+    }
+
+    if rows.is_empty() {
+        return Err(Failure {
+            code: EXIT_DATASET,
+            message: "no datasets were found".into(),
+        });
+    }
+
+    print_rows(&["Year".into(), "Rounds".into()], &rows, format);
+
+    Ok(EXIT_SUCCESS)
+}
+
+fn show(catalog: &Catalog, args: &ShowArgs) -> Result<u8, Failure> {
+    let options = Options {
+        year: Some(args.year),
+        round: args
+            .round
+            .or_else(|| catalog.get_rounds(Some(args.year)).last()),
+        all_rounds: args.all_rounds,
+    };
+
+    let branch_families = match &args.branch_families {
+        Some(path) => BranchFamilies::read(path),
+        None => BranchFamilies::from_env(),
+    }
+    .map_err(Failure::dataset)?;
+
+    let mut dataset = Dataset::with_branch_families(branch_families);
+    dataset
+        .load(&options, &catalog.get_paths(&options))
+        .map_err(Failure::dataset)?;
+
+    let (filters, dictionaries) = dataset.get_filters_with_dictionaries();
+
+    select(
+        &mut filters.branch,
+        &dictionaries.branch,
+        &args.branch,
+        "branch",
+        contains,
+    )?;
+
+    // Families select among the branches, rather than having a filter of their own.
+    if !args.family.is_empty() {
+        let mut families = BitSet::new(dictionaries.family.len(), false);
+
+        select(
+            &mut families,
+            &dictionaries.family,
+            &args.family,
+            "family",
+            contains,
+        )?;
+
+        for (branch, family) in dictionaries.family_of.iter().enumerate() {
+            if !families.contains(*family as usize) {
+                filters.branch.set(branch, false);
+            }
+        }
+    }
+
+    select(
+        &mut filters.duration,
+        &dictionaries.duration,
+        &args
+            .duration
+            .iter()
+            .map(|&years| format_duration(years))
+            .collect::<Vec<_>>(),
+        "duration",
+        equals,
+    )?;
+    select(
+        &mut filters.degree,
+        &dictionaries.degree,
+        &args.degree,
+        "degree",
+        contains,
+    )?;
+    select(
+        &mut filters.institute,
+        &dictionaries.institute,
+        &args.institute,
+        "institute",
+        matches_institute,
+    )?;
+    select(
+        &mut filters.institute_kind,
+        &dictionaries.institute_kind,
+        &args.institute_type,
+        "institute type",
+        contains,
+    )?;
+    select(
+        &mut filters.quota,
+        &dictionaries.quota,
+        &args.quota,
+        "quota",
+        equals,
+    )?;
+    select(
+        &mut filters.seat_type,
+        &dictionaries.seat_type,
+        &args.seat_type,
+        "seat type",
+        equals,
+    )?;
+    select(
+        &mut filters.gender,
+        &dictionaries.gender,
+        &args.gender,
+        "gender",
+        equals,
+    )?;
+
+    // Rank ranges apply to both exams.
+    for ranks in [&mut filters.advanced, &mut filters.main] {
+        if let Some(range) = args.opening {
+            ranks.or = range;
+        }
+
+        if let Some(range) = args.closing {
+            ranks.cr = range;
+        }
+
+        if args.no_preparatory {
+            ranks.or_preparatory = false;
+            ranks.cr_preparatory = false;
+        }
+    }
+
+    if let Some(search) = &args.search {
+        filters.set_search(dictionaries, search);
+    }
+
+    if let Some(home_state) = &args.home_state {
+        filters.set_home_state(dictionaries, Some(home_state));
+        filters.eligible_quotas = true;
+    }
+
+    dataset.sort(&Sort::with_keys(args.sort.clone()));
+
+    let rounds = dataset.get_rounds();
+    let dictionaries = dataset.get_dictionaries();
+
+    let mut header: Vec<String> = [
+        "Institute",
+        "Branch",
+        "Quota",
+        "Seat type",
+        "Gender",
+        "Exam",
+    ]
+    .map(String::from)
+    .into();
+
+    if args.all_rounds {
+        for round in rounds {
+            header.push(format!("OR (R{round})"));
+            header.push(format!("CR (R{round})"));
+        }
+    } else {
+        header.push("Opening rank".into());
+        header.push("Closing rank".into());
+    }
+
+    let entries = dataset.get_entries().take(args.limit.unwrap_or(usize::MAX));
+
+    if entries.len() == 0 {
+        eprintln!("orcr: no rows match the filters");
+        return Ok(EXIT_NO_MATCHES);
+    }
+
+    let format = match args.format {
+        ShowFormat::Table => Format::Table,
+        ShowFormat::Tsv => Format::Tsv,
+        ShowFormat::Csv => return write_export(ExportFormat::Csv, &dataset, entries),
+        ShowFormat::Json => return write_export(ExportFormat::Json, &dataset, entries),
+        ShowFormat::Markdown => return write_export(ExportFormat::Markdown, &dataset, entries),
+    };
+
+    let rows: Vec<Vec<String>> = entries
+        .map(|entry| get_row(entry, dictionaries, args.all_rounds))
+        .collect();
+
+    print_rows(&header, &rows, format);
+
+    Ok(EXIT_SUCCESS)
+}
+
+fn import(args: &ImportArgs) -> Result<u8, Failure> {
+    let path = Options::new(args.year, args.round)
+        .get_db_path(&args.into)
+        .map_err(Failure::dataset)?;
+
+    if path.exists() && !args.force {
+        return Err(Failure::usage(format!(
+            "{} already exists, use --force to replace it",
+            path.display()
+        )));
+    }
+
+    let mapping = args
+        .columns
+        .iter()
+        .fold(HeaderMapping::default(), |mapping, (field, header)| {
+            mapping.with_header(*field, header)
+        });
+
+    let mut import = Import::default();
+
+    for file in &args.files {
+        let is_csv = file
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+
+        let source = if is_csv {
+            read_csv(file, &mapping)
+        } else {
+            read_html(file)
+        }
+        .map_err(Failure::dataset)?;
+
+        for rejected in &source.rejected {
+            eprintln!(
+                "orcr: {}:{}: skipped row: {}",
+                file.display(),
+                rejected.line,
+                rejected.reason
+            );
+        }
+
+        import.rows.extend(source.rows);
+    }
+
+    if import.rows.is_empty() {
+        return Err(Failure {
+            code: EXIT_DATASET,
+            message: "no rows were found".into(),
+        });
+    }
+
+    let summary = import.write(&path).map_err(|error| Failure {
+        code: EXIT_OUTPUT,
+        message: error.to_string(),
+    })?;
+
+    println!(
+        "Imported {} rows, {} with preparatory ranks, into {}",
+        summary.written,
+        summary.preparatory,
+        path.display()
+    );
+
+    Ok(EXIT_SUCCESS)
+}
+
+fn write_export<'a>(
+    format: ExportFormat,
+    dataset: &Dataset,
+    entries: impl Iterator<Item = &'a Entry>,
+) -> Result<u8, Failure> {
+    let mut stdout = std::io::stdout().lock();
+
+    export(format, &mut stdout, dataset, entries).map_err(|error| Failure {
+        code: EXIT_OUTPUT,
+        message: format!("couldn't write the output: {error}"),
+    })?;
+
+    Ok(EXIT_SUCCESS)
+}
+
+/// Select only the values of `dictionary` matching any of `patterns`,
+/// or leave every value selected if there are no patterns.
+fn select(
+    selected: &mut BitSet,
+    dictionary: &Dictionary,
+    patterns: &[String],
+    column: &str,
+    matches: fn(&str, &str) -> bool,
+) -> Result<(), Failure> {
+    if patterns.is_empty() {
+        return Ok(());
+    }
+
+    selected.set_all(false);
+
+    for pattern in patterns {
+        let mut found = false;
+
+        for (id, value) in dictionary.values().iter().enumerate() {
+            if matches(&normalize_name(value), pattern) {
+                selected.set(id, true);
+                found = true;
+            }
+        }
+
+        if !found {
+            return Err(Failure::usage(format!("no {column} matches {pattern:?}")));
+        }
+    }
+
+    Ok(())
+}
+
+fn contains(value: &str, pattern: &str) -> bool {
+    value.to_lowercase().contains(&pattern.to_lowercase())
+}
+
+/// Parse a state or union territory from [`STATES`], ignoring case.
+fn parse_state(text: &str) -> Result<String, String> {
+    STATES
+        .iter()
+        .find(|state| state.eq_ignore_ascii_case(text.trim()))
+        .map(|state| state.to_string())
+        .ok_or_else(|| format!("unknown state {text:?}"))
+}
+
+/// Institutes are also matched by the short name of the bundled institute.
+fn matches_institute(value: &str, pattern: &str) -> bool {
+    contains(value, pattern)
+        || find_institute(value)
+            .is_some_and(|institute| institute.short_name.eq_ignore_ascii_case(pattern))
+}
+
+fn equals(value: &str, pattern: &str) -> bool {
+    value.eq_ignore_ascii_case(pattern)
+}
+
+fn get_row(entry: &Entry, dictionaries: &Dictionaries, rounds: bool) -> Vec<String> {
+    let mut row = vec![
+        normalize_name(dictionaries.institute.get(entry.institute)),
+        normalize_name(dictionaries.branch.get(entry.branch)),
+        dictionaries.quota.get(entry.quota).into(),
+        dictionaries.seat_type.get(entry.seat_type).into(),
+        dictionaries.gender.get(entry.gender).into(),
+        entry.domain.to_string(),
+    ];
+
+    if rounds {
+        for ranks in &entry.rounds {
+            match ranks {
+                Some(ranks) => {
+                    row.push(ranks.or.to_string());
+                    row.push(ranks.cr.to_string());
+                }
+
+                // Not offered in this round
+                None => {
+                    row.push("-".into());
+                    row.push("-".into());
+                }
+            }
+        }
+    } else {
+        row.push(entry.or.to_string());
+        row.push(entry.cr.to_string());
+    }
+
+    row
+}
+
+fn print_rows(header: &[String], rows: &[Vec<String>], format: Format) {
+    match format {
+        Format::Table => {
+            let mut widths: Vec<usize> = header.iter().map(|cell| cell.chars().count()).collect();
+
+            for row in rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+
+            let print = |row: &[String]| {
+                let cells: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{cell:width$}"))
+                    .collect();
+
+                println!("{}", cells.join("  ").trim_end());
+            };
+
+            print(header);
+
+            for row in rows {
+                print(row);
+            }
+        }
+
+        Format::Tsv => {
+            println!("{}", header.join("\t"));
+
+            for row in rows {
+                println!("{}", row.join("\t"));
+            }
+        }
+    }
+}
+
+/// Parse a header mapping such as `or=Opening Rank`.
+fn parse_column(text: &str) -> Result<(ImportField, String), String> {
+    let (field, header) = text
+        .split_once('=')
+        .ok_or_else(|| format!("expected a field and header like or=Opening, found {text:?}"))?;
+
+    Ok((field.trim().parse()?, header.into()))
+}
+
+/// Parse a range of ranks such as `1..5000`, `..5000` or `1000..`.
+fn parse_range(text: &str) -> Result<RankRange, String> {
+    let (start, end) = text
+        .split_once("..")
+        .ok_or_else(|| format!("expected a range like 1..5000, found {text:?}"))?;
+
+    let parse = |bound: &str, default: u32| match bound.trim() {
+        "" => Ok(default),
+        bound => bound
+            .parse::<u32>()
+            .map_err(|_| format!("invalid rank {bound:?}")),
+    };
+
+    let range = RankRange::new(parse(start, 0)?, parse(end, u32::MAX)?);
+
+    if range.is_empty() {
+        return Err(format!("the range {text:?} is empty"));
+    }
+
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use josaa_orcr::{SortColumn, SortDirection};
+
+    #[test]
+    fn range_parsing() {
+        assert_eq!(parse_range("1..5000"), Ok(RankRange::new(1, 5000)));
+        assert_eq!(parse_range("..5000"), Ok(RankRange::new(0, 5000)));
+        assert_eq!(parse_range("1000.."), Ok(RankRange::new(1000, u32::MAX)));
+
+        // Invalid
+        assert!(parse_range("5000").is_err());
+        assert!(parse_range("a..b").is_err());
+        assert!(parse_range("5000..1").is_err());
+    }
+
+    #[test]
+    fn cli_arguments() {
+        let cli = Cli::try_parse_from([
+            "orcr", "show", "--year", "2024", "--quota", "AI", "--quota", "OS", "--cr", "1..5000",
+        ])
+        .unwrap();
+
+        let Command::Show(args) = cli.command else {
+            panic!("Expected the show command.");
+        };
+
+        assert_eq!(args.quota, ["AI", "OS"]);
+        assert_eq!(args.closing, Some(RankRange::new(1, 5000)));
+        assert!(args.round.is_none());
+        assert_eq!(Sort::with_keys(args.sort), Sort::default());
+
+        // Sorting by several columns
+        let cli = Cli::try_parse_from([
+            "orcr",
+            "show",
+            "--year",
+            "2024",
+            "--sort",
+            "institute",
+            "--sort",
+            "or-desc",
+        ])
+        .unwrap();
+
+        let Command::Show(args) = cli.command else {
+            panic!("Expected the show command.");
+        };
+
+        assert_eq!(
+            args.sort.iter().map(|key| key.column).collect::<Vec<_>>(),
+            [SortColumn::Institute, SortColumn::Opening]
+        );
+        assert_eq!(args.sort[1].direction, SortDirection::Descending);
+
+        // States are matched ignoring case
+        let cli = Cli::try_parse_from(["orcr", "show", "--year", "2024", "--home-state", "kerala"])
+            .unwrap();
+
+        let Command::Show(args) = cli.command else {
+            panic!("Expected the show command.");
+        };
+
+        assert_eq!(args.home_state.as_deref(), Some("Kerala"));
+        assert!(
+            Cli::try_parse_from(["orcr", "show", "--year", "2024", "--home-state", "Atlantis"])
+                .is_err()
+        );
+
+        // A round and every round can't both be selected
+        assert!(
+            Cli::try_parse_from([
+                "orcr",
+                "show",
+                "--year",
+                "2024",
+                "--round",
+                "1",
+                "--all-rounds"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn extra_roots() {
+        let root = std::env::temp_dir().join(format!("orcr-empty-root-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+
+        let cli = Cli::try_parse_from(["orcr", "--db", root.to_str().unwrap(), "list"]).unwrap();
+        let catalog = open_catalog(cli.roots);
+
+        // The default datasets are still found
+        assert!(catalog.get_years().any(|year| year == 2024));
+        assert!(matches!(list(&catalog, Format::Table), Ok(EXIT_SUCCESS)));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn import_arguments() {
+        let cli = Cli::try_parse_from([
+            "orcr",
+            "import",
+            "--year",
+            "2025",
+            "--round",
+            "1",
+            "--column",
+            "or=Opening Rank",
+            "ranks.csv",
+        ])
+        .unwrap();
+
+        let Command::Import(args) = cli.command else {
+            panic!("Expected the import command.");
+        };
+
+        assert_eq!(
+            args.columns,
+            [(ImportField::OpeningRank, "Opening Rank".to_string())]
+        );
+        assert_eq!(args.into, PathBuf::from(DEFAULT_ROOT));
+
+        // Unknown fields and missing files
+        assert!(parse_column("rank=Rank").is_err());
+        assert!(parse_column("or").is_err());
+        assert!(Cli::try_parse_from(["orcr", "import", "--year", "2025", "--round", "1"]).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{Error, Options, Result};
+use rusqlite::{Connection, OpenFlags};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// The directory datasets are looked up in by default.
+pub const DEFAULT_ROOT: &str = "db";
+
+/// Environment variable holding extra dataset roots, separated
+/// the same way as `PATH`.
+pub const EXTRA_ROOTS_VAR: &str = "JOSAA_ORCR_DB";
+
+/// Columns a dataset must have, for each table.
+const SCHEMA: [(&str, &[&str]); 2] = [
+    (
+        "data",
+        &[
+            "institute",
+            "branch",
+            "quota",
+            "seatType",
+            "gender",
+            "orank",
+            "crank",
+        ],
+    ),
+    ("institutes", &["institute", "instituteType"]),
+];
+
+/// Optional columns of the `data` table, which are `1` where `orank` or `crank` is
+/// a preparatory rank. Datasets without them only have regular ranks.
+pub const PREPARATORY_COLUMNS: [&str; 2] = ["orankPreparatory", "crankPreparatory"];
+
+/// Open the dataset at `path` for reading, checking that it has the expected schema.
+pub fn open_dataset(path: &Path) -> Result<Connection> {
+    // Checked first, since SQLite doesn't say why it can't open a file.
+    std::fs::metadata(path).map_err(|error| Error::Io {
+        path: path.into(),
+        error,
+    })?;
+
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    for (table, columns) in SCHEMA {
+        let found = get_columns(&conn, table)?;
+
+        if let Some(column) = columns
+            .iter()
+            .find(|column| !found.contains(&column.to_string()))
+        {
+            return Err(Error::Schema {
+                path: path.into(),
+                table: table.into(),
+                column: column.to_string(),
+            });
+        }
+    }
+
+    Ok(conn)
+}
+
+/// Get the columns of the `data` table to select for [`Ranks::from_row`](crate::Ranks::from_row),
+/// which are the opening and closing ranks followed by whether each is a preparatory rank.
+pub fn get_rank_columns(conn: &Connection) -> Result<&'static str> {
+    let found = get_columns(conn, "data")?;
+
+    if PREPARATORY_COLUMNS
+        .iter()
+        .all(|column| found.iter().any(|found| found == column))
+    {
+        Ok("orank, crank, COALESCE(orankPreparatory, 0), COALESCE(crankPreparatory, 0)")
+    } else {
+        Ok("orank, crank, 0, 0")
+    }
+}
+
+fn get_columns(conn: &Connection, table: &str) -> rusqlite::Result<Vec<String>> {
+    conn.prepare(&format!("PRAGMA table_info({table});"))?
+        .query_map([], |row| row.get(1))?
+        .collect()
+}
+
+/// An index of every dataset available on disk.
+///
+/// Each root is expected to follow the `<root>/<year>/data-<year>-<round>.db`
+/// layout. When the same year and round exist under several roots,
+/// the one from the earlier root is used.
+#[derive(Default)]
+pub struct Catalog {
+    roots: Vec<PathBuf>,
+    datasets: BTreeMap<u16, BTreeMap<u8, PathBuf>>,
+    rejected: Vec<PathBuf>,
+}
+
+impl Catalog {
+    /// Create a catalog over the [default roots](Catalog::get_default_roots), and scan it.
+    pub fn new() -> Self {
+        Self::with_roots(Self::get_default_roots())
+    }
+
+    /// Get [`DEFAULT_ROOT`] followed by any roots listed in [`EXTRA_ROOTS_VAR`].
+    pub fn get_default_roots() -> Vec<PathBuf> {
+        let mut roots = vec![PathBuf::from(DEFAULT_ROOT)];
+
+        if let Some(extra) = std::env::var_os(EXTRA_ROOTS_VAR) {
+            roots.extend(std::env::split_paths(&extra));
+        }
+
+        roots
+    }
+
+    /// Create a catalog over the given roots, and scan it.
+    pub fn with_roots(roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        let mut catalog = Self {
+            roots: roots.into_iter().collect(),
+            ..Default::default()
+        };
+
+        catalog.scan();
+        catalog
+    }
+
+    /// Rebuild the index from the contents of the roots.
+    ///
+    /// Roots or directories which can't be read are skipped, and files
+    /// which don't match the expected schema are recorded as rejected.
+    pub fn scan(&mut self) {
+        self.datasets.clear();
+        self.rejected.clear();
+
+        for root in &self.roots {
+            let Ok(years) = root.read_dir() else {
+                continue;
+            };
+
+            for year_dir in years.flatten() {
+                let Ok(files) = year_dir.path().read_dir() else {
+                    continue;
+                };
+
+                for file in files.flatten() {
+                    let path = file.path();
+
+                    let Some(Options {
+                        year: Some(year),
+                        round: Some(round),
+                        ..
+                    }) = path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .and_then(Options::from_file_name)
+                    else {
+                        continue;
+                    };
+
+                    // The file should be inside the directory for its year.
+                    if year_dir.file_name().to_str() != Some(&year.to_string()) {
+                        continue;
+                    }
+
+                    let rounds = self.datasets.entry(year).or_default();
+
+                    if rounds.contains_key(&round) {
+                        continue;
+                    }
+
+                    if open_dataset(&path).is_ok() {
+                        rounds.insert(round, path);
+                    } else {
+                        self.rejected.push(path);
+                    }
+                }
+            }
+        }
+
+        self.datasets.retain(|_, rounds| !rounds.is_empty());
+        self.rejected.sort();
+    }
+
+    /// Get every year with at least one dataset, in ascending order.
+    pub fn get_years(&self) -> impl Iterator<Item = u16> + '_ {
+        self.datasets.keys().copied()
+    }
+
+    /// Get every round available for `year`, in ascending order.
+    pub fn get_rounds(&self, year: Option<u16>) -> impl Iterator<Item = u8> + '_ {
+        year.and_then(|year| self.datasets.get(&year))
+            .into_iter()
+            .flat_map(|rounds| rounds.keys().copied())
+    }
+
+    /// Get the path to the dataset selected by `options`, if it exists.
+    pub fn get_path(&self, options: &Options) -> Option<&Path> {
+        self.datasets
+            .get(&options.year?)?
+            .get(&options.round?)
+            .map(PathBuf::as_path)
+    }
+
+    /// Get the round numbers and paths of every dataset selected by `options`,
+    /// which is every round of the year if [`Options::all_rounds`] is set.
+    pub fn get_paths(&self, options: &Options) -> Vec<(u8, &Path)> {
+        if options.all_rounds {
+            options
+                .year
+                .and_then(|year| self.datasets.get(&year))
+                .into_iter()
+                .flatten()
+                .map(|(round, path)| (*round, path.as_path()))
+                .collect()
+        } else {
+            self.get_path(options)
+                .zip(options.round)
+                .map(|(path, round)| (round, path))
+                .into_iter()
+                .collect()
+        }
+    }
+
+    /// Get the files which were found but didn't match the expected schema.
+    pub fn get_rejected(&self) -> &[PathBuf] {
+        &self.rejected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_scans_bundled_datasets() {
+        let catalog = Catalog::with_roots([PathBuf::from(DEFAULT_ROOT)]);
+
+        assert_eq!(catalog.get_years().next(), Some(2016));
+        assert_eq!(catalog.get_years().last(), Some(2024));
+
+        assert_eq!(
+            catalog.get_rounds(Some(2016)).collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(catalog.get_rounds(Some(2019)).count(), 7);
+        assert_eq!(catalog.get_rounds(Some(2024)).count(), 5);
+        assert!(catalog.get_rejected().is_empty());
+    }
+
+    #[test]
+    fn catalog_handles_missing_datasets() {
+        let catalog = Catalog::with_roots([PathBuf::from(DEFAULT_ROOT)]);
+
+        // Unknown years and rounds
+        assert_eq!(catalog.get_rounds(Some(1999)).count(), 0);
+        assert_eq!(catalog.get_rounds(None).count(), 0);
+        assert!(catalog.get_path(&Options::new(2024, 7)).is_none());
+        assert!(catalog.get_path(&Options::default()).is_none());
+
+        // Known dataset
+        assert_eq!(
+            catalog.get_path(&Options::new(2024, 5)),
+            Some(Options::new(2024, 5).into_db_path().unwrap().as_path())
+        );
+
+        // All rounds of a year
+        let options = Options {
+            year: Some(2020),
+            round: None,
+            all_rounds: true,
+        };
+
+        assert_eq!(
+            catalog
+                .get_paths(&options)
+                .into_iter()
+                .map(|(round, _)| round)
+                .collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(catalog.get_paths(&Options::new(2020, 3)).len(), 1);
+        assert!(catalog.get_paths(&Options::new(2020, 9)).is_empty());
+
+        // Files which aren't datasets
+        assert!(matches!(
+            open_dataset(Path::new("db/does-not-exist.db")),
+            Err(Error::Io { .. })
+        ));
+        assert!(open_dataset(Path::new("Cargo.toml")).is_err());
+
+        // Missing roots
+        let catalog = Catalog::with_roots([PathBuf::from("does-not-exist")]);
+
+        assert_eq!(catalog.get_years().count(), 0);
+    }
+}
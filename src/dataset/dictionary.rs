@@ -0,0 +1,315 @@
+use crate::{
+    BranchFamilies, Entry, EntryKey, Error, Program, Result, canonicalize_institute, find_institute,
+};
+use rustc_hash::FxHashMap;
+
+/// The type given to institutes which are missing from the `institutes` table.
+pub const UNKNOWN_INSTITUTE_TYPE: &str = "Unknown";
+
+/// The distinct values of a single text column, each with a
+/// compact id which is its index in [`Dictionary::values`].
+#[derive(Clone, Default)]
+pub struct Dictionary {
+    values: Vec<String>,
+    ids: FxHashMap<String, u16>,
+}
+
+impl Dictionary {
+    /// Get the id of `value`, adding it if it isn't in the dictionary yet.
+    ///
+    /// Fails with [`Error::TooManyValues`] if there are more
+    /// distinct values than fit in a `u16`.
+    pub fn intern(&mut self, value: &str) -> Result<u16> {
+        if let Some(id) = self.ids.get(value) {
+            return Ok(*id);
+        }
+
+        let id = u16::try_from(self.values.len()).map_err(|_| Error::TooManyValues)?;
+
+        self.values.push(value.into());
+        self.ids.insert(value.into(), id);
+
+        Ok(id)
+    }
+
+    /// Get the value for `id`.
+    ///
+    /// # Panics
+    /// If `id` wasn't returned by this dictionary.
+    pub fn get(&self, id: u16) -> &str {
+        &self.values[id as usize]
+    }
+
+    /// Get the id of `value`, if it is in the dictionary.
+    pub fn get_id(&self, value: &str) -> Option<u16> {
+        self.ids.get(value).copied()
+    }
+
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Dictionaries for every text column of the loaded datasets.
+#[derive(Clone, Default)]
+pub struct Dictionaries {
+    pub institute: Dictionary,
+    pub institute_kind: Dictionary,
+    pub branch: Dictionary,
+    pub quota: Dictionary,
+    pub seat_type: Dictionary,
+    pub gender: Dictionary,
+    /// Program durations, such as `4 Years`, parsed from the branches.
+    pub duration: Dictionary,
+    /// Degrees, such as `B.Tech`, parsed from the branches.
+    pub degree: Dictionary,
+    /// Families of related branches, in the order of their [`BranchFamilies`].
+    pub family: Dictionary,
+
+    /// The id of each institute's type, indexed by institute id.
+    pub kind_of: Vec<u16>,
+    /// The id in [`Dictionaries::institute`] of every name institutes were read under.
+    institute_names: FxHashMap<String, u16>,
+
+    /// The ids of each branch's duration and degree, indexed by branch id.
+    pub program_of: Vec<(u16, u16)>,
+    /// The id of each branch's family, indexed by branch id.
+    pub family_of: Vec<u16>,
+}
+
+impl Dictionaries {
+    /// Get the id of the institute published as `name`, adding it under its
+    /// [canonical name](canonicalize_institute) if it isn't in the dictionary yet.
+    ///
+    /// Every spelling of an institute gets the same id.
+    pub fn intern_institute_name(&mut self, name: &str) -> Result<u16> {
+        if let Some(id) = self.institute_names.get(name) {
+            return Ok(*id);
+        }
+
+        let id = self.institute.intern(&canonicalize_institute(name))?;
+
+        self.institute_names.insert(name.into(), id);
+
+        Ok(id)
+    }
+
+    /// Record that the institute published as `institute` is of type `kind`,
+    /// returning the ids of both.
+    pub fn intern_institute(&mut self, institute: &str, kind: &str) -> Result<(u16, u16)> {
+        let institute = self.intern_institute_name(institute)?;
+        let kind = self.institute_kind.intern(kind)?;
+
+        // Institutes before it which are only in the data have no type yet.
+        if self.kind_of.len() < institute as usize {
+            let unknown = self.institute_kind.intern(UNKNOWN_INSTITUTE_TYPE)?;
+
+            self.kind_of.resize(institute as usize, unknown);
+        }
+
+        if self.kind_of.len() == institute as usize {
+            self.kind_of.push(kind);
+        } else {
+            self.kind_of[institute as usize] = kind;
+        }
+
+        Ok((institute, kind))
+    }
+
+    /// Get the type id of the institute with id `institute`, giving
+    /// it [`UNKNOWN_INSTITUTE_TYPE`] if its type was never recorded.
+    pub fn resolve_kind(&mut self, institute: u16) -> Result<u16> {
+        if let Some(kind) = self.kind_of.get(institute as usize) {
+            return Ok(*kind);
+        }
+
+        let unknown = self.institute_kind.intern(UNKNOWN_INSTITUTE_TYPE)?;
+
+        self.kind_of.resize(institute as usize + 1, unknown);
+
+        Ok(unknown)
+    }
+
+    /// Parse the program of every branch which hasn't been parsed yet,
+    /// recording its duration, degree and family from `families`.
+    pub fn resolve_programs(&mut self, families: &BranchFamilies) -> Result<()> {
+        // Families are added up front, so that their ids follow the order of the taxonomy.
+        if self.family.is_empty() {
+            for family in families.get_families() {
+                self.family.intern(family)?;
+            }
+        }
+
+        for branch in &self.branch.values()[self.program_of.len()..] {
+            let program = Program::parse(branch);
+
+            self.program_of.push((
+                self.duration.intern(&program.get_duration_text())?,
+                self.degree.intern(&program.degree.to_string())?,
+            ));
+            self.family_of.push(
+                self.family
+                    .intern(families.get_family(&program.discipline))?,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Get the type of `institute`, if it is in the loaded datasets.
+    pub fn get_institute_kind(&self, institute: &str) -> Option<&str> {
+        let id = self.institute.get_id(institute)?;
+
+        self.kind_of
+            .get(id as usize)
+            .map(|kind| self.institute_kind.get(*kind))
+    }
+
+    /// Get the stable id of the bundled institute with id `institute`, if it is bundled.
+    pub fn get_institute_id(&self, institute: u16) -> Option<u16> {
+        find_institute(self.institute.get(institute)).map(|institute| institute.id)
+    }
+
+    /// Get the text values identifying `entry`.
+    pub fn get_key(&self, entry: &Entry) -> EntryKey {
+        EntryKey {
+            institute: self.institute.get(entry.institute).into(),
+            branch: self.branch.get(entry.branch).into(),
+            quota: self.quota.get(entry.quota).into(),
+            seat_type: self.seat_type.get(entry.seat_type).into(),
+            gender: self.gender.get(entry.gender).into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UNKNOWN_DURATION;
+
+    #[test]
+    fn dictionary_intern() {
+        let mut dictionary = Dictionary::default();
+
+        assert_eq!(dictionary.intern("AI").unwrap(), 0);
+        assert_eq!(dictionary.intern("HS").unwrap(), 1);
+        assert_eq!(dictionary.intern("AI").unwrap(), 0);
+
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(dictionary.get(1), "HS");
+        assert_eq!(dictionary.get_id("AI"), Some(0));
+        assert_eq!(dictionary.get_id("OS"), None);
+
+        // Running out of ids
+        for i in 0..u16::MAX as usize - 1 {
+            dictionary.intern(&i.to_string()).unwrap();
+        }
+
+        assert!(matches!(
+            dictionary.intern("One too many"),
+            Err(Error::TooManyValues)
+        ));
+    }
+
+    #[test]
+    fn dictionaries_institute_kinds() {
+        let mut dictionaries = Dictionaries::default();
+
+        dictionaries.intern_institute("NIT Goa", "NIT").unwrap();
+        dictionaries.intern_institute("IIT Goa", "IIT").unwrap();
+        dictionaries.intern_institute("NIT Patna", "NIT").unwrap();
+
+        assert_eq!(dictionaries.kind_of, [0, 1, 0]);
+        assert_eq!(dictionaries.get_institute_kind("IIT Goa"), Some("IIT"));
+        assert_eq!(dictionaries.get_institute_kind("IIT Patna"), None);
+
+        // Institutes without a recorded type
+        let patna = dictionaries.institute.intern("IIT Patna").unwrap();
+
+        assert_eq!(dictionaries.resolve_kind(patna).unwrap(), 2);
+        assert_eq!(
+            dictionaries.get_institute_kind("IIT Patna"),
+            Some(UNKNOWN_INSTITUTE_TYPE)
+        );
+
+        // Every spelling of a bundled institute has the same id
+        let ism = dictionaries
+            .intern_institute_name("Indian School of Mines Dhanbad")
+            .unwrap();
+
+        assert_eq!(
+            dictionaries
+                .intern_institute_name("Indian Institute of Technology (ISM)  Dhanbad")
+                .unwrap(),
+            ism
+        );
+        assert_eq!(
+            dictionaries.institute.get(ism),
+            "Indian Institute of Technology (ISM) Dhanbad"
+        );
+        assert!(dictionaries.get_institute_id(ism).is_some());
+        assert_eq!(dictionaries.get_institute_id(patna), None);
+    }
+
+    #[test]
+    fn dictionaries_institute_kind_gaps() {
+        let mut dictionaries = Dictionaries::default();
+
+        // An institute only in the data, before any type is recorded
+        let patna = dictionaries.intern_institute_name("IIT Patna").unwrap();
+        let (goa, nit) = dictionaries.intern_institute("NIT Goa", "NIT").unwrap();
+
+        assert_eq!(dictionaries.kind_of[goa as usize], nit);
+        assert_eq!(
+            dictionaries.get_institute_kind("IIT Patna"),
+            Some(UNKNOWN_INSTITUTE_TYPE)
+        );
+        let unknown = dictionaries.resolve_kind(patna).unwrap();
+
+        assert_eq!(
+            dictionaries.institute_kind.get(unknown),
+            UNKNOWN_INSTITUTE_TYPE
+        );
+
+        // Recording its type later replaces the unknown one
+        let (_, iit) = dictionaries.intern_institute("IIT Patna", "IIT").unwrap();
+
+        assert_eq!(dictionaries.resolve_kind(patna).unwrap(), iit);
+    }
+
+    #[test]
+    fn dictionaries_programs() {
+        let mut dictionaries = Dictionaries::default();
+
+        for branch in [
+            "Civil Engineering (4 Years, Bachelor of Technology)",
+            "Architecture (5 Years, Bachelor of Architecture)",
+            "Chemistry (5 Years, Integrated Master of Science)",
+        ] {
+            dictionaries.branch.intern(branch).unwrap();
+        }
+
+        let families = BranchFamilies::default();
+
+        dictionaries.resolve_programs(&families).unwrap();
+
+        assert_eq!(dictionaries.program_of, [(0, 0), (1, 1), (1, 2)]);
+        assert_eq!(dictionaries.duration.values(), ["4 Years", "5 Years"]);
+        assert_eq!(dictionaries.degree.get(2), "Integrated M.Sc");
+
+        // Only new branches are parsed
+        dictionaries.branch.intern("Physics").unwrap();
+        dictionaries.resolve_programs(&families).unwrap();
+
+        assert_eq!(dictionaries.program_of[3], (2, 3));
+        assert_eq!(dictionaries.duration.get(2), UNKNOWN_DURATION);
+    }
+}
@@ -1,23 +1,74 @@
-use crate::Filters;
+use crate::types::{Rank, RankDomain};
 
-pub struct Entry {
+/// The opening and closing ranks of an entry in a single round.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Ranks {
+    pub or: Rank,
+    pub cr: Rank,
+}
+
+impl Ranks {
+    /// Read the ranks from the columns given by [`get_rank_columns`](crate::get_rank_columns),
+    /// starting at `index`.
+    pub fn from_row(row: &rusqlite::Row, index: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            or: Rank::new(row.get(index)?, row.get(index + 2)?),
+            cr: Rank::new(row.get(index + 1)?, row.get(index + 3)?),
+        })
+    }
+}
+
+/// The columns which identify a program across rounds and years.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct EntryKey {
     pub institute: String,
     pub branch: String,
     pub quota: String,
     pub seat_type: String,
     pub gender: String,
-    pub or: u32,
-    pub cr: u32,
 }
 
+/// A row of the loaded datasets. Text columns are stored as ids
+/// into the matching [`Dictionary`](crate::Dictionary) of [`Dictionaries`](crate::Dictionaries).
+pub struct Entry {
+    pub institute: u16,
+    pub institute_kind: u16,
+    pub branch: u16,
+    pub quota: u16,
+    pub seat_type: u16,
+    pub gender: u16,
+
+    /// The duration and degree of the program, parsed from the branch.
+    pub duration: u16,
+    pub degree: u16,
+    /// The family of the branch, from the [`BranchFamilies`](crate::BranchFamilies) of the dataset.
+    pub family: u16,
+
+    /// The exam [`Entry::or`] and [`Entry::cr`] are ranks of,
+    /// derived from the type of the institute.
+    pub domain: RankDomain,
+
+    /// Ranks from the last loaded round the entry appears in.
+    pub or: Rank,
+    pub cr: Rank,
+
+    /// Ranks for every loaded round, or [`None`]
+    /// if the entry doesn't appear in that round.
+    pub rounds: Vec<Option<Ranks>>,
+}
+
+/// An iterator over the entries at a list of indices.
 pub struct EntryIterator<'a> {
-    filters: &'a Filters,
+    indices: std::slice::Iter<'a, usize>,
     entries: &'a [Entry],
 }
 
 impl<'a> EntryIterator<'a> {
-    pub fn new(filters: &'a Filters, entries: &'a [Entry]) -> Self {
-        Self { filters, entries }
+    pub fn new(entries: &'a [Entry], indices: &'a [usize]) -> Self {
+        Self {
+            indices: indices.iter(),
+            entries,
+        }
     }
 }
 
@@ -25,13 +76,12 @@ impl<'a> Iterator for EntryIterator<'a> {
     type Item = &'a Entry;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            let entry = self.entries.first()?;
-            self.entries = &self.entries[1..];
+        self.indices.next().map(|&index| &self.entries[index])
+    }
 
-            if self.filters.matches(entry) {
-                return Some(entry);
-            }
-        }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
     }
 }
+
+impl ExactSizeIterator for EntryIterator<'_> {}
@@ -0,0 +1,207 @@
+use crate::{Error, Result, normalize_name};
+use std::path::Path;
+
+/// The family of disciplines which don't match any keyword.
+pub const OTHER_FAMILY: &str = "Other";
+
+/// Environment variable holding the path of a file to read
+/// branch families from, instead of the bundled ones.
+pub const BRANCH_FAMILIES_VAR: &str = "JOSAA_ORCR_BRANCH_FAMILIES";
+
+/// Bundled branch families, as tab-separated `family` and `keyword` columns.
+const BUNDLED_FAMILIES: &str = include_str!("../../data/branch_families.tsv");
+
+/// A taxonomy grouping related disciplines into families, such as
+/// `Computer Science` for both `Computer Science and Engineering` and
+/// `Mathematics and Computing`.
+///
+/// Each family has keywords which are looked for in the discipline, ignoring
+/// case. The keyword found first in the discipline decides its family, and the
+/// longest of them if several start at the same place.
+#[derive(Clone, Debug)]
+pub struct BranchFamilies {
+    /// Families in the order they are first listed, without [`OTHER_FAMILY`].
+    families: Vec<String>,
+    /// Lowercase keywords, along with the index of their family.
+    keywords: Vec<(String, usize)>,
+}
+
+impl Default for BranchFamilies {
+    /// The bundled branch families.
+    fn default() -> Self {
+        Self::parse(BUNDLED_FAMILIES).expect("the bundled branch families are valid")
+    }
+}
+
+impl BranchFamilies {
+    /// Parse branch families from tab-separated `family` and `keyword`
+    /// columns, after a header row.
+    ///
+    /// Fails with the line number of the first row missing either column.
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let mut families = BranchFamilies {
+            families: Vec::new(),
+            keywords: Vec::new(),
+        };
+
+        for (i, line) in text.lines().enumerate().skip(1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut columns = line
+                .split('\t')
+                .map(str::trim)
+                .map(|column| Some(column).filter(|column| !column.is_empty()));
+
+            let (Some(Some(family)), Some(Some(keyword))) = (columns.next(), columns.next()) else {
+                return Err(format!("line {}: expected a family and a keyword", i + 1));
+            };
+
+            let index = match families.families.iter().position(|name| name == family) {
+                Some(index) => index,
+                None => {
+                    families.families.push(family.into());
+                    families.families.len() - 1
+                }
+            };
+
+            families.keywords.push((keyword.to_lowercase(), index));
+        }
+
+        Ok(families)
+    }
+
+    /// Read branch families from a file, with the format of [`BranchFamilies::parse`].
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|error| Error::Io {
+            path: path.into(),
+            error,
+        })?;
+
+        Self::parse(&text).map_err(|reason| Error::BranchFamilies {
+            path: path.into(),
+            reason,
+        })
+    }
+
+    /// Read branch families from the file in [`BRANCH_FAMILIES_VAR`]
+    /// if it is set, or else use the bundled ones.
+    pub fn from_env() -> Result<Self> {
+        match std::env::var_os(BRANCH_FAMILIES_VAR) {
+            Some(path) => Self::read(Path::new(&path)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Get every family in order, ending with [`OTHER_FAMILY`].
+    pub fn get_families(&self) -> impl Iterator<Item = &str> {
+        self.families
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(OTHER_FAMILY))
+    }
+
+    /// Get the family of `discipline`, such as one from [`Program::parse`](crate::Program::parse).
+    pub fn get_family(&self, discipline: &str) -> &str {
+        let discipline = normalize_name(discipline).to_lowercase();
+
+        self.keywords
+            .iter()
+            .filter_map(|(keyword, family)| {
+                discipline
+                    .find(keyword.as_str())
+                    .map(|position| (position, std::cmp::Reverse(keyword.len()), *family))
+            })
+            .min()
+            .map_or(OTHER_FAMILY, |(_, _, family)| &self.families[family])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Catalog, DEFAULT_ROOT, Dataset, Options, Program};
+
+    #[test]
+    fn branch_families() {
+        let families = BranchFamilies::default();
+
+        for (discipline, family) in [
+            ("Computer Science and Engineering", "Computer Science"),
+            (
+                "Computer Science and Engineering (Artificial Intelligence)",
+                "Computer Science",
+            ),
+            ("Mathematics and Computing", "Computer Science"),
+            ("Mathematics &amp; Computing", "Computer Science"),
+            ("Electrical and Electronics Engineering", "Electrical"),
+            (
+                "Electronics and Electrical Communication Engineering",
+                "Electronics",
+            ),
+            (
+                "Mechanical Engineering and M.Tech. in Computer Integrated Manufacturing",
+                "Mechanical",
+            ),
+            ("Industrial Chemistry", "Sciences"),
+            ("Mathematics", "Sciences"),
+            ("B.Tech in General Engineering", OTHER_FAMILY),
+        ] {
+            assert_eq!(families.get_family(discipline), family, "{discipline:?}");
+        }
+
+        assert_eq!(families.get_families().next(), Some("Computer Science"));
+        assert_eq!(families.get_families().last(), Some(OTHER_FAMILY));
+
+        // Custom families
+        let families =
+            BranchFamilies::parse("family\tkeyword\nCircuits\telectronic\nCircuits\tElectrical\n")
+                .unwrap();
+
+        assert_eq!(
+            families.get_family("Electrical Engineering"),
+            families.get_family("Electronics Engineering")
+        );
+        assert_eq!(families.get_family("Civil Engineering"), OTHER_FAMILY);
+        assert_eq!(
+            families.get_families().collect::<Vec<_>>(),
+            ["Circuits", OTHER_FAMILY]
+        );
+
+        assert_eq!(
+            BranchFamilies::parse("family\tkeyword\nCircuits\n")
+                .err()
+                .unwrap(),
+            "line 2: expected a family and a keyword"
+        );
+    }
+
+    #[test]
+    fn branch_families_cover_bundled_datasets() {
+        let families = BranchFamilies::default();
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options {
+            year: catalog.get_years().last(),
+            round: None,
+            all_rounds: true,
+        };
+
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let other: Vec<&String> = dataset
+            .get_dictionaries()
+            .branch
+            .values()
+            .iter()
+            .filter(|branch| {
+                families.get_family(&Program::parse(branch).discipline) == OTHER_FAMILY
+            })
+            .collect();
+
+        assert!(other.len() <= 2, "{other:?}");
+    }
+}
@@ -1,90 +1,216 @@
-use crate::{Entry, types::RankRange};
-use rusqlite::Connection;
-use rustc_hash::FxHashMap;
-
+use crate::{
+    Dictionaries, Entry, Search, is_quota_eligible,
+    types::{BitSet, Rank, RankDomain, RankRange},
+};
+
+/// Opening and closing rank filters for the ranks of a single [`RankDomain`].
+///
+/// The ranges only apply to regular ranks. Preparatory ranks come after every regular
+/// rank, so they are either all shown or all hidden.
 #[derive(Clone, Default)]
-pub struct Filters {
-    pub institute_kinds: FxHashMap<String, (bool, FxHashMap<String, bool>)>,
-    pub branch: FxHashMap<String, bool>,
-    pub quota: FxHashMap<String, bool>,
-    pub seat_type: FxHashMap<String, bool>,
-    pub gender: FxHashMap<String, bool>,
+pub struct RankFilters {
     pub or: RankRange,
     pub cr: RankRange,
 
+    /// Whether entries with a preparatory opening or closing rank are shown.
+    pub or_preparatory: bool,
+    pub cr_preparatory: bool,
+
+    /// The range of regular ranks in the loaded datasets.
     pub or_bounds: RankRange,
     pub cr_bounds: RankRange,
+
+    /// Whether the loaded datasets have any preparatory ranks in this domain.
+    pub has_preparatory: bool,
 }
 
-impl Filters {
-    fn get_uniques(
-        conn: &Connection,
-        field: &'static str,
-    ) -> rusqlite::Result<FxHashMap<String, bool>> {
-        conn.prepare(&format!("SELECT DISTINCT {field} FROM data;"))?
-            .query_map([], |row| Ok((row.get(0)?, true)))?
-            .collect()
+impl RankFilters {
+    /// Whether the loaded datasets have any ranks in this domain.
+    pub fn is_empty(&self) -> bool {
+        self.or_bounds.is_empty() && self.cr_bounds.is_empty() && !self.has_preparatory
     }
 
-    fn get_max(conn: &Connection, field: &'static str) -> rusqlite::Result<u32> {
-        conn.prepare(&format!("SELECT MAX({field}) FROM data;"))?
-            .query_one([], |row| row.get(0))
+    /// Whether an opening rank of `or` and a closing rank of `cr` are selected.
+    pub fn contains(&self, or: Rank, cr: Rank) -> bool {
+        let contains = |range: &RankRange, preparatory: bool, rank: Rank| match rank {
+            Rank::Regular(rank) => range.contains(rank),
+            Rank::Preparatory(_) => preparatory,
+        };
+
+        contains(&self.or, self.or_preparatory, or) && contains(&self.cr, self.cr_preparatory, cr)
     }
 
-    fn get_institutes(&mut self, conn: &Connection) -> rusqlite::Result<()> {
-        let kinds = conn
-            .prepare("SELECT DISTINCT instituteType FROM institutes;")?
-            .query_map([], |row| row.get(0))?
-            .collect::<rusqlite::Result<Vec<String>>>()?;
+    /// Grow the bounds so that they include `entry`, selecting all of them.
+    fn extend(&mut self, entry: &Entry) {
+        for (range, rank) in [
+            (&mut self.or_bounds, entry.or),
+            (&mut self.cr_bounds, entry.cr),
+        ] {
+            let Rank::Regular(rank) = rank else {
+                self.has_preparatory = true;
+                continue;
+            };
+
+            *range = if range.is_empty() {
+                (0..=rank).into()
+            } else {
+                (0..=range.end.max(rank)).into()
+            };
+        }
 
-        for kind in kinds {
-            let institutes = conn
-                .prepare("SELECT institute FROM institutes WHERE instituteType = ?1;")?
-                .query_map([&kind], |row| Ok((row.get(0)?, true)))?
-                .collect::<rusqlite::Result<FxHashMap<String, bool>>>()?;
+        self.or = self.or_bounds;
+        self.cr = self.cr_bounds;
+        self.or_preparatory = true;
+        self.cr_preparatory = true;
+    }
+}
+
+/// The selected values of every column. Each [`BitSet`] is indexed
+/// by the ids from the matching [`Dictionary`](crate::Dictionary).
+#[derive(Clone, Default)]
+pub struct Filters {
+    pub institute_kind: BitSet,
+    pub institute: BitSet,
+    pub branch: BitSet,
+    pub quota: BitSet,
+    pub seat_type: BitSet,
+    pub gender: BitSet,
+    pub duration: BitSet,
+    pub degree: BitSet,
+
+    /// Whether only seats in quotas a candidate from the home state
+    /// is eligible for are shown. See [`Filters::set_home_state`].
+    pub eligible_quotas: bool,
+    home_state: Option<String>,
+    /// Whether a candidate from `home_state` is eligible for each pair of institute
+    /// and quota, indexed by `institute * quotas + quota`.
+    quota_eligibility: BitSet,
+
+    /// The free-text search entries must also match. See [`Filters::set_search`].
+    search: Search,
+
+    /// Rank filters for the IITs.
+    pub advanced: RankFilters,
+    /// Rank filters for every other institute.
+    pub main: RankFilters,
+}
 
-            self.institute_kinds.insert(kind, (true, institutes));
+impl Filters {
+    /// Create filters which select every value in `dictionaries`,
+    /// with rank bounds covering every one of `entries`.
+    pub fn new(dictionaries: &Dictionaries, entries: &[Entry]) -> Self {
+        let mut filters = Self {
+            institute_kind: BitSet::new(dictionaries.institute_kind.len(), true),
+            institute: BitSet::new(dictionaries.institute.len(), true),
+            branch: BitSet::new(dictionaries.branch.len(), true),
+            quota: BitSet::new(dictionaries.quota.len(), true),
+            seat_type: BitSet::new(dictionaries.seat_type.len(), true),
+            gender: BitSet::new(dictionaries.gender.len(), true),
+            duration: BitSet::new(dictionaries.duration.len(), true),
+            degree: BitSet::new(dictionaries.degree.len(), true),
+            ..Default::default()
+        };
+
+        filters.set_home_state(dictionaries, None);
+
+        for entry in entries {
+            match entry.domain {
+                RankDomain::Advanced => filters.advanced.extend(entry),
+                RankDomain::Main => filters.main.extend(entry),
+            }
         }
 
-        Ok(())
+        filters
     }
 
-    pub fn load(&mut self, conn: &Connection) -> rusqlite::Result<()> {
-        self.branch = Self::get_uniques(conn, "branch")?;
-        self.quota = Self::get_uniques(conn, "quota")?;
-        self.seat_type = Self::get_uniques(conn, "seatType")?;
-        self.gender = Self::get_uniques(conn, "gender")?;
+    /// Set the state the candidate is domiciled in, which decides the quotas
+    /// shown when [`Filters::eligible_quotas`] is set, as in [`is_quota_eligible`].
+    pub fn set_home_state(&mut self, dictionaries: &Dictionaries, home_state: Option<&str>) {
+        let quotas = dictionaries.quota.values();
 
-        self.or = (0..=(Self::get_max(conn, "orank"))?).into();
-        self.cr = (0..=(Self::get_max(conn, "crank"))?).into();
+        self.home_state = home_state.map(Into::into);
+        self.quota_eligibility = BitSet::new(dictionaries.institute.len() * quotas.len(), false);
 
-        self.or_bounds = self.or;
-        self.cr_bounds = self.cr;
+        for (institute, name) in dictionaries.institute.values().iter().enumerate() {
+            for (quota, value) in quotas.iter().enumerate() {
+                self.quota_eligibility.set(
+                    institute * quotas.len() + quota,
+                    is_quota_eligible(value, name, home_state),
+                );
+            }
+        }
+    }
 
-        self.get_institutes(conn)?;
+    pub fn get_home_state(&self) -> Option<&str> {
+        self.home_state.as_deref()
+    }
 
-        Ok(())
+    /// Only show entries matching `query`, as in [`Search`].
+    pub fn set_search(&mut self, dictionaries: &Dictionaries, query: &str) {
+        self.search = Search::new(query, dictionaries);
     }
 
-    pub fn matches(&self, entry: &Entry) -> bool {
-        if !(*self.branch.get(&entry.branch).unwrap()
-            && *self.quota.get(&entry.quota).unwrap()
-            && *self.seat_type.get(&entry.seat_type).unwrap()
-            && *self.gender.get(&entry.gender).unwrap()
-            && self.or.contains(entry.or)
-            && self.cr.contains(entry.cr))
-        {
-            return false;
+    pub fn get_search(&self) -> &Search {
+        &self.search
+    }
+
+    /// Get the stable ids of the selected institutes which are in the bundled list,
+    /// which refer to the same institutes in every year.
+    pub fn get_institutes(&self, dictionaries: &Dictionaries) -> Vec<u16> {
+        (0..dictionaries.institute.len())
+            .filter(|&institute| self.institute.contains(institute))
+            .filter_map(|institute| dictionaries.get_institute_id(institute as u16))
+            .collect()
+    }
+
+    /// Select only the institutes with the stable ids `ids`, along with every
+    /// institute which isn't in the bundled list.
+    pub fn set_institutes(&mut self, dictionaries: &Dictionaries, ids: &[u16]) {
+        for institute in 0..dictionaries.institute.len() {
+            let selected = dictionaries
+                .get_institute_id(institute as u16)
+                .is_none_or(|id| ids.contains(&id));
+
+            self.institute.set(institute, selected);
         }
+    }
 
-        let mut matches_institute = false;
+    /// Select the institute types with any selected institute, and no others,
+    /// so that the types follow institutes which were selected one by one.
+    pub fn select_institute_kinds(&mut self, dictionaries: &Dictionaries) {
+        self.institute_kind.set_all(false);
 
-        for (kind_enabled, institutes) in self.institute_kinds.values() {
-            if let Some(institute_enabled) = institutes.get(&entry.institute) {
-                matches_institute = *kind_enabled && *institute_enabled;
+        for (institute, kind) in dictionaries.kind_of.iter().enumerate() {
+            if self.institute.contains(institute) {
+                self.institute_kind.set(*kind as usize, true);
             }
         }
+    }
+
+    /// Get the rank filters for `domain`.
+    pub fn get_ranks(&self, domain: RankDomain) -> &RankFilters {
+        match domain {
+            RankDomain::Advanced => &self.advanced,
+            RankDomain::Main => &self.main,
+        }
+    }
 
-        matches_institute
+    pub fn matches(&self, entry: &Entry) -> bool {
+        let ranks = self.get_ranks(entry.domain);
+
+        self.institute_kind.contains(entry.institute_kind as usize)
+            && self.institute.contains(entry.institute as usize)
+            && self.branch.contains(entry.branch as usize)
+            && self.quota.contains(entry.quota as usize)
+            && (!self.eligible_quotas
+                || self
+                    .quota_eligibility
+                    .contains(entry.institute as usize * self.quota.len() + entry.quota as usize))
+            && self.seat_type.contains(entry.seat_type as usize)
+            && self.gender.contains(entry.gender as usize)
+            && self.duration.contains(entry.duration as usize)
+            && self.degree.contains(entry.degree as usize)
+            && ranks.contains(entry.or, entry.cr)
+            && self.search.matches(entry)
     }
 }
@@ -0,0 +1,380 @@
+use crate::types::RankDomain;
+use rustc_hash::FxHashMap;
+use std::sync::LazyLock;
+
+/// States and union territories of India, used for home state quotas.
+pub const STATES: [&str; 36] = [
+    "Andaman and Nicobar Islands",
+    "Andhra Pradesh",
+    "Arunachal Pradesh",
+    "Assam",
+    "Bihar",
+    "Chandigarh",
+    "Chhattisgarh",
+    "Dadra and Nagar Haveli and Daman and Diu",
+    "Delhi",
+    "Goa",
+    "Gujarat",
+    "Haryana",
+    "Himachal Pradesh",
+    "Jammu and Kashmir",
+    "Jharkhand",
+    "Karnataka",
+    "Kerala",
+    "Ladakh",
+    "Lakshadweep",
+    "Madhya Pradesh",
+    "Maharashtra",
+    "Manipur",
+    "Meghalaya",
+    "Mizoram",
+    "Nagaland",
+    "Odisha",
+    "Puducherry",
+    "Punjab",
+    "Rajasthan",
+    "Sikkim",
+    "Tamil Nadu",
+    "Telangana",
+    "Tripura",
+    "Uttar Pradesh",
+    "Uttarakhand",
+    "West Bengal",
+];
+
+/// Bundled list of institutes, as tab-separated `id`, `institute`, `shortName`,
+/// `state`, `city` and `instituteType` columns.
+const INSTITUTES: &str = include_str!("../../data/institutes.tsv");
+
+/// Names the bundled institutes were published under, as tab-separated
+/// `id`, `firstYear`, `lastYear` and `alias` columns.
+const ALIASES: &str = include_str!("../../data/institute_aliases.tsv");
+
+/// An institute from the bundled list, with an id which stays the
+/// same across years, even when the institute is renamed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Institute {
+    pub id: u16,
+    /// The current name of the institute, without artifacts like double spaces.
+    pub name: &'static str,
+    /// A short name, such as `IIT BBS`.
+    pub short_name: &'static str,
+    pub state: &'static str,
+    pub city: &'static str,
+    /// The `instituteType` in the latest dataset the institute appears in.
+    pub kind: &'static str,
+}
+
+/// A name a bundled institute was published under, in the years from
+/// `first_year` to `last_year`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InstituteAlias {
+    pub id: u16,
+    pub first_year: u16,
+    pub last_year: u16,
+    pub name: &'static str,
+}
+
+static BUNDLED_INSTITUTES: LazyLock<Vec<Institute>> = LazyLock::new(|| {
+    INSTITUTES
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split('\t');
+
+            Some(Institute {
+                id: columns.next()?.parse().ok()?,
+                name: columns.next()?,
+                short_name: columns.next()?,
+                state: columns.next()?,
+                city: columns.next()?,
+                kind: columns.next()?,
+            })
+        })
+        .collect()
+});
+
+static BUNDLED_ALIASES: LazyLock<Vec<InstituteAlias>> = LazyLock::new(|| {
+    ALIASES
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split('\t');
+
+            Some(InstituteAlias {
+                id: columns.next()?.parse().ok()?,
+                first_year: columns.next()?.parse().ok()?,
+                last_year: columns.next()?.parse().ok()?,
+                name: columns.next()?,
+            })
+        })
+        .collect()
+});
+
+/// The index into [`BUNDLED_INSTITUTES`] of every current name and alias, by [`get_lookup_key`].
+static INSTITUTE_INDICES: LazyLock<FxHashMap<String, usize>> = LazyLock::new(|| {
+    let indices: FxHashMap<u16, usize> = BUNDLED_INSTITUTES
+        .iter()
+        .enumerate()
+        .map(|(index, institute)| (institute.id, index))
+        .collect();
+
+    BUNDLED_INSTITUTES
+        .iter()
+        .map(|institute| (institute.id, institute.name))
+        .chain(BUNDLED_ALIASES.iter().map(|alias| (alias.id, alias.name)))
+        .filter_map(|(id, name)| Some((get_lookup_key(name), *indices.get(&id)?)))
+        .collect()
+});
+
+/// Normalize an institute name as it appears in a dataset, by
+/// unescaping `&amp;` and collapsing runs of whitespace.
+pub fn normalize_name(name: &str) -> String {
+    name.replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Names are looked up ignoring case, since some years spell them in capitals.
+fn get_lookup_key(name: &str) -> String {
+    normalize_name(name).to_lowercase()
+}
+
+/// Get every bundled institute.
+pub fn get_institutes() -> &'static [Institute] {
+    &BUNDLED_INSTITUTES
+}
+
+/// Get the bundled institute with the stable id `id`.
+pub fn get_institute(id: u16) -> Option<&'static Institute> {
+    BUNDLED_INSTITUTES
+        .iter()
+        .find(|institute| institute.id == id)
+}
+
+/// Find the bundled institute which has gone by `name` in any year, regardless of artifacts.
+pub fn find_institute(name: &str) -> Option<&'static Institute> {
+    INSTITUTE_INDICES
+        .get(&get_lookup_key(name))
+        .map(|&index| &BUNDLED_INSTITUTES[index])
+}
+
+/// Get the names the institute with the stable id `id` was published under, oldest first.
+pub fn get_aliases(id: u16) -> impl Iterator<Item = &'static InstituteAlias> {
+    let mut aliases: Vec<_> = BUNDLED_ALIASES
+        .iter()
+        .filter(|alias| alias.id == id)
+        .collect();
+
+    aliases.sort_by_key(|alias| alias.first_year);
+    aliases.into_iter()
+}
+
+/// Get the name `institute` is shown under in every year: the current name
+/// of the bundled institute, or else the name without artifacts.
+pub fn canonicalize_institute(institute: &str) -> String {
+    match find_institute(institute) {
+        Some(institute) => institute.name.into(),
+        None => normalize_name(institute),
+    }
+}
+
+/// Get the state or union territory an institute is located in,
+/// if it is in the bundled list.
+pub fn get_state(institute: &str) -> Option<&'static str> {
+    find_institute(institute).map(|institute| institute.state)
+}
+
+/// Infer an institute's `instituteType` as JoSAA groups them, from the bundled
+/// list or otherwise from its name.
+///
+/// Institutes which aren't IITs, NITs or IIITs are government funded technical institutions.
+/// IIEST Shibpur is grouped with the NITs, as it has been since 2020.
+pub fn infer_institute_type(institute: &str) -> &'static str {
+    if let Some(institute) = find_institute(institute) {
+        return institute.kind;
+    }
+
+    let name = get_lookup_key(institute);
+
+    // Checked first, since their names contain "Indian Institute of ... Technology".
+    if name.contains("indian institute of information technology") {
+        "Indian Institute of Information Technology"
+    } else if name.contains("indian institute of technology")
+        || name.contains("indian school of mines")
+    {
+        RankDomain::ADVANCED_INSTITUTE_TYPE
+    } else if name.contains("national institute of technology")
+        || name.contains("indian institute of engineering science and technology")
+    {
+        "National Institute of Technology"
+    } else {
+        "Government Funded Technical Institutions"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Catalog, DEFAULT_ROOT, Options};
+    use rusqlite::{Connection, OpenFlags};
+
+    #[test]
+    fn institute_states() {
+        // Names are matched regardless of artifacts
+        assert_eq!(
+            get_state("Indian Institute  of Technology Bombay"),
+            Some("Maharashtra")
+        );
+        assert_eq!(
+            get_state("School of Planning &amp; Architecture, Bhopal"),
+            Some("Madhya Pradesh")
+        );
+        assert_eq!(
+            get_state("National Institute of Technology Arunachal Pradesh "),
+            Some("Arunachal Pradesh")
+        );
+
+        // Every state in the list is known
+        assert!(
+            get_institutes()
+                .iter()
+                .all(|institute| STATES.contains(&institute.state))
+        );
+
+        // Unknown institutes
+        assert_eq!(get_state("Hogwarts"), None);
+    }
+
+    #[test]
+    fn institute_identities() {
+        let dhanbad = find_institute("Indian Institute of Technology (ISM) Dhanbad").unwrap();
+
+        assert_eq!(dhanbad.short_name, "IIT ISM");
+        assert_eq!(dhanbad.city, "Dhanbad");
+        assert_eq!(get_institute(dhanbad.id), Some(dhanbad));
+
+        // Former names and artifacts refer to the same institute
+        assert_eq!(
+            find_institute("Indian School of Mines Dhanbad"),
+            Some(dhanbad)
+        );
+        assert_eq!(
+            canonicalize_institute("Indian School of  Mines Dhanbad"),
+            dhanbad.name
+        );
+        assert_eq!(
+            canonicalize_institute("INDIAN INSTITUTE OF INFORMATION TECHNOLOGY SENAPATI MANIPUR"),
+            canonicalize_institute("Indian Institute of Information Technology Manipur")
+        );
+
+        let aliases: Vec<(u16, u16)> = get_aliases(dhanbad.id)
+            .map(|alias| (alias.first_year, alias.last_year))
+            .collect();
+
+        assert_eq!(aliases, [(2016, 2016), (2017, 2024)]);
+
+        // Ids and short names are unique
+        let mut ids: Vec<u16> = get_institutes()
+            .iter()
+            .map(|institute| institute.id)
+            .collect();
+        let mut short_names: Vec<&str> = get_institutes()
+            .iter()
+            .map(|institute| institute.short_name)
+            .collect();
+
+        ids.sort();
+        ids.dedup();
+        short_names.sort();
+        short_names.dedup();
+
+        assert_eq!(ids.len(), get_institutes().len());
+        assert_eq!(short_names.len(), get_institutes().len());
+
+        // Unknown institutes only lose their artifacts
+        assert_eq!(find_institute("Hogwarts"), None);
+        assert_eq!(
+            canonicalize_institute("Hogwarts  &amp; Co"),
+            "Hogwarts & Co"
+        );
+    }
+
+    #[test]
+    fn institute_types() {
+        // Bundled institutes
+        assert_eq!(
+            infer_institute_type("Indian Institute  of Technology Bhubaneswar"),
+            "Indian Institute of Technology"
+        );
+        assert_eq!(
+            infer_institute_type("Indian School of Mines Dhanbad"),
+            "Indian Institute of Technology"
+        );
+        assert_eq!(
+            infer_institute_type("INDIAN INSTITUTE OF INFORMATION TECHNOLOGY SENAPATI MANIPUR"),
+            "Indian Institute of Information Technology"
+        );
+        assert_eq!(
+            infer_institute_type("Dr. B R Ambedkar National Institute of Technology, Jalandhar"),
+            "National Institute of Technology"
+        );
+        assert_eq!(
+            infer_institute_type("Indian Institute of Engineering Science and Technology, Shibpur"),
+            "National Institute of Technology"
+        );
+        assert_eq!(
+            infer_institute_type("Birla Institute of Technology, Mesra,  Ranchi"),
+            "Government Funded Technical Institutions"
+        );
+
+        // Unknown institutes are inferred from their names
+        assert_eq!(
+            infer_institute_type("Indian Institute of Technology Atlantis"),
+            "Indian Institute of Technology"
+        );
+        assert_eq!(
+            infer_institute_type("Indian Institute of Information Technology, Gondor"),
+            "Indian Institute of Information Technology"
+        );
+        assert_eq!(
+            infer_institute_type("National Institute of Technology Narnia"),
+            "National Institute of Technology"
+        );
+        assert_eq!(
+            infer_institute_type("Hogwarts School of Witchcraft and Wizardry"),
+            "Government Funded Technical Institutions"
+        );
+    }
+
+    #[test]
+    fn institutes_cover_bundled_datasets() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+
+        for year in catalog.get_years() {
+            for round in catalog.get_rounds(Some(year)) {
+                let path = catalog.get_path(&Options::new(year, round)).unwrap();
+                let conn =
+                    Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+                let mut stmt = conn
+                    .prepare("SELECT institute, instituteType FROM institutes;")
+                    .unwrap();
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))
+                    .unwrap();
+
+                for row in rows {
+                    let (institute, kind): (String, String) = row.unwrap();
+
+                    assert!(get_state(&institute).is_some(), "{institute:?}");
+
+                    // IIEST Shibpur was a government funded technical institution until 2020.
+                    if year >= 2020 {
+                        assert_eq!(infer_institute_type(&institute), kind, "{institute:?}");
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{BranchFamilies, Dataset, Error, Options, Result};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
+    },
+};
+
+enum Message {
+    Progress(f32),
+    Done(Box<Result<Dataset>>),
+}
+
+/// Loads a [`Dataset`] on a background thread.
+///
+/// Dropping the loader cancels it.
+pub struct Loader {
+    options: Options,
+    receiver: Receiver<Message>,
+    cancelled: Arc<AtomicBool>,
+    progress: f32,
+}
+
+impl Loader {
+    /// Start loading the datasets for `rounds`, which are selected by `options`,
+    /// grouping their branches into `branch_families`.
+    ///
+    /// `notify` is called from the background thread whenever there is progress,
+    /// such as to request a repaint.
+    pub fn spawn(
+        options: Options,
+        rounds: Vec<(u8, PathBuf)>,
+        branch_families: BranchFamilies,
+        notify: impl Fn() + Send + 'static,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+
+        std::thread::spawn(move || {
+            let rounds: Vec<(u8, &Path)> = rounds
+                .iter()
+                .map(|(round, path)| (*round, path.as_path()))
+                .collect();
+
+            let mut dataset = Dataset::with_branch_families(branch_families);
+            let result = dataset.load_with_progress(&options, &rounds, |progress| {
+                // Sending only fails if the loader was dropped.
+                let sent = sender.send(Message::Progress(progress)).is_ok();
+                notify();
+
+                sent && !flag.load(Ordering::Relaxed)
+            });
+
+            let _ = sender.send(Message::Done(Box::new(result.map(|_| dataset))));
+            notify();
+        });
+
+        Self {
+            options,
+            receiver,
+            cancelled,
+            progress: 0.0,
+        }
+    }
+
+    /// Get the options of the datasets being loaded.
+    pub fn get_options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Get the fraction of rows read so far, as of the last [`Loader::poll`].
+    pub fn get_progress(&self) -> f32 {
+        self.progress
+    }
+
+    /// Stop loading. [`Loader::poll`] will then return [`Error::Cancelled`].
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Check on the background thread without blocking.
+    ///
+    /// Returns [`None`] while still loading, or the result once it is done.
+    pub fn poll(&mut self) -> Option<Result<Dataset>> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Message::Progress(progress)) => self.progress = progress,
+                Ok(Message::Done(result)) => return Some(*result),
+                Err(TryRecvError::Empty) => return None,
+
+                // The thread stopped without a result.
+                Err(TryRecvError::Disconnected) => return Some(Err(Error::Cancelled)),
+            }
+        }
+    }
+}
+
+impl Drop for Loader {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Catalog, DEFAULT_ROOT};
+
+    fn wait(loader: &mut Loader) -> Result<Dataset> {
+        loop {
+            if let Some(result) = loader.poll() {
+                return result;
+            }
+
+            std::thread::yield_now();
+        }
+    }
+
+    fn get_rounds(catalog: &Catalog, options: &Options) -> Vec<(u8, PathBuf)> {
+        catalog
+            .get_paths(options)
+            .into_iter()
+            .map(|(round, path)| (round, path.into()))
+            .collect()
+    }
+
+    #[test]
+    fn loader_loads_in_background() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options::new(2024, 5);
+
+        let mut loader = Loader::spawn(
+            options,
+            get_rounds(&catalog, &options),
+            BranchFamilies::default(),
+            || (),
+        );
+        let dataset = wait(&mut loader).unwrap();
+
+        assert!(dataset.is_loaded());
+        assert_eq!(dataset.get_options(), &options);
+        assert_eq!(loader.get_progress(), 1.0);
+    }
+
+    #[test]
+    fn loader_cancels() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options {
+            year: Some(2019),
+            round: None,
+            all_rounds: true,
+        };
+
+        let mut loader = Loader::spawn(
+            options,
+            get_rounds(&catalog, &options),
+            BranchFamilies::default(),
+            || (),
+        );
+
+        loader.cancel();
+
+        assert!(matches!(wait(&mut loader), Err(Error::Cancelled)));
+    }
+}
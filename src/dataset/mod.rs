@@ -1,22 +1,55 @@
+mod catalog;
+mod dictionary;
 mod entry;
+mod families;
 mod filters;
+mod institutes;
+mod loader;
 mod options;
+mod predictor;
+mod program;
+mod query;
+mod search;
 mod sort;
+mod trend;
 
+pub use catalog::*;
+pub use dictionary::*;
 pub use entry::*;
+pub use families::*;
 pub use filters::*;
+pub use institutes::*;
+pub use loader::*;
 pub use options::*;
+pub use predictor::*;
+pub use program::*;
+pub use query::*;
+pub use search::*;
 pub use sort::*;
+pub use trend::*;
 
-use rusqlite::{Connection, OpenFlags};
+use crate::{Error, Result, types::RankDomain};
+use rusqlite::Connection;
+use rustc_hash::FxHashMap;
+use std::path::Path;
+
+/// How many rows are read between reports of progress while loading.
+const PROGRESS_INTERVAL: usize = 1024;
 
 #[derive(Default)]
 pub struct Dataset {
     connection: Option<Connection>,
     connection_options: Options,
+    attached: Vec<String>,
 
+    rounds: Vec<u8>,
+    dictionaries: Dictionaries,
+    branch_families: BranchFamilies,
     entries: Vec<Entry>,
     filters: Filters,
+
+    /// Indices of the entries matching `filters`, in sorted order.
+    filtered: Vec<usize>,
 }
 
 impl Dataset {
@@ -24,50 +57,601 @@ impl Dataset {
         self.connection.is_some()
     }
 
-    pub fn load(&mut self, options: &Options) -> rusqlite::Result<()> {
-        if self.connection_options == *options {
+    /// Create an empty dataset which groups branches into `branch_families` once loaded.
+    pub fn with_branch_families(branch_families: BranchFamilies) -> Self {
+        Self {
+            branch_families,
+            ..Default::default()
+        }
+    }
+
+    /// Get the options the dataset was loaded with.
+    pub fn get_options(&self) -> &Options {
+        &self.connection_options
+    }
+
+    /// Load the datasets for `rounds`, which are selected by `options`.
+    /// `rounds` holds the round number and path of each dataset.
+    ///
+    /// Rows are joined across rounds on their institute, branch, quota,
+    /// seat type and gender. Does nothing if the same datasets are already loaded.
+    ///
+    /// If loading fails, the previously loaded datasets are kept.
+    pub fn load(&mut self, options: &Options, rounds: &[(u8, &Path)]) -> Result<()> {
+        self.load_with_progress(options, rounds, |_| true)
+    }
+
+    /// Like [`Dataset::load`], but calls `progress` with the fraction of
+    /// rows read so far, between `0.0` and `1.0`.
+    ///
+    /// Loading stops with [`Error::Cancelled`] as soon as `progress` returns `false`.
+    pub fn load_with_progress(
+        &mut self,
+        options: &Options,
+        rounds: &[(u8, &Path)],
+        mut progress: impl FnMut(f32) -> bool,
+    ) -> Result<()> {
+        if self.connection_options == *options && self.is_loaded() {
             return Ok(());
         }
 
-        let connection =
-            Connection::open_with_flags(options.into_db_path(), OpenFlags::SQLITE_OPEN_READ_ONLY)?;
-
-        self.entries = connection
-            .prepare("SELECT institute, branch, quota, seatType, gender, orank, crank FROM data")?
-            .query_map([], |row| {
-                Ok(Entry {
-                    institute: row.get(0)?,
-                    branch: row.get(1)?,
-                    quota: row.get(2)?,
-                    seat_type: row.get(3)?,
-                    gender: row.get(4)?,
-                    or: row.get(5)?,
-                    cr: row.get(6)?,
-                })
-            })?
-            .collect::<rusqlite::Result<Vec<Entry>>>()?;
+        if rounds.is_empty() {
+            return Err(Error::InvalidOptions(*options));
+        }
+
+        let mut connection = None;
+        let mut dictionaries = Dictionaries::default();
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut indices: FxHashMap<[u16; 5], usize> = FxHashMap::default();
+
+        for (i, (_, path)) in rounds.iter().enumerate() {
+            if !progress(i as f32 / rounds.len() as f32) {
+                return Err(Error::Cancelled);
+            }
+
+            let conn = open_dataset(path)?;
+
+            // Statements borrow the connection, so they are dropped before it is kept.
+            {
+                let total: usize =
+                    conn.query_one("SELECT COUNT(*) FROM data;", [], |row| row.get(0))?;
+
+                let mut stmt = conn.prepare("SELECT institute, instituteType FROM institutes;")?;
+                let mut rows = stmt.query([])?;
+
+                while let Some(row) = rows.next()? {
+                    dictionaries
+                        .intern_institute(row.get_ref(0)?.as_str()?, row.get_ref(1)?.as_str()?)?;
+                }
+
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT institute, branch, quota, seatType, gender, {} FROM data;",
+                    get_rank_columns(&conn)?
+                ))?;
+                let mut rows = stmt.query([])?;
+                let mut read = 0;
+
+                while let Some(row) = rows.next()? {
+                    read += 1;
 
-        self.filters.load(&connection)?;
-        self.connection = Some(connection);
+                    if read % PROGRESS_INTERVAL == 0
+                        && !progress((i as f32 + read as f32 / total as f32) / rounds.len() as f32)
+                    {
+                        return Err(Error::Cancelled);
+                    }
+
+                    let key = [
+                        dictionaries.intern_institute_name(row.get_ref(0)?.as_str()?)?,
+                        dictionaries.branch.intern(row.get_ref(1)?.as_str()?)?,
+                        dictionaries.quota.intern(row.get_ref(2)?.as_str()?)?,
+                        dictionaries.seat_type.intern(row.get_ref(3)?.as_str()?)?,
+                        dictionaries.gender.intern(row.get_ref(4)?.as_str()?)?,
+                    ];
+                    let ranks = Ranks::from_row(row, 5)?;
+
+                    let index = *indices.entry(key).or_insert_with(|| {
+                        let [institute, branch, quota, seat_type, gender] = key;
+
+                        entries.push(Entry {
+                            institute,
+                            institute_kind: 0,
+                            branch,
+                            quota,
+                            seat_type,
+                            gender,
+                            duration: 0,
+                            degree: 0,
+                            family: 0,
+                            domain: RankDomain::Main,
+                            or: ranks.or,
+                            cr: ranks.cr,
+                            rounds: vec![None; rounds.len()],
+                        });
+
+                        entries.len() - 1
+                    });
+
+                    // Later rounds overwrite the ranks of earlier ones.
+                    let entry = &mut entries[index];
+
+                    entry.or = ranks.or;
+                    entry.cr = ranks.cr;
+                    entry.rounds[i] = Some(ranks);
+                }
+            }
+
+            connection = Some(conn);
+        }
+
+        if !progress(1.0) {
+            return Err(Error::Cancelled);
+        }
+
+        dictionaries.resolve_programs(&self.branch_families)?;
+
+        // Institute types are only known once every round is read.
+        for entry in &mut entries {
+            (entry.duration, entry.degree) = dictionaries.program_of[entry.branch as usize];
+            entry.family = dictionaries.family_of[entry.branch as usize];
+
+            entry.institute_kind = dictionaries.resolve_kind(entry.institute)?;
+            entry.domain = RankDomain::from_institute_type(
+                dictionaries.institute_kind.get(entry.institute_kind),
+            );
+        }
+
+        self.filters = Filters::new(&dictionaries, &entries);
+        self.dictionaries = dictionaries;
+        self.entries = entries;
+        self.rounds = rounds.iter().map(|(round, _)| *round).collect();
+        self.connection = connection;
         self.connection_options = *options;
+        self.attached.clear();
+        self.apply_filters();
 
         Ok(())
     }
 
+    /// Sort the entries by every key of `sort`, with text columns in alphabetical order.
     pub fn sort(&mut self, sort: &Sort) {
-        self.entries.sort_by(match sort {
-            Sort::OpeningAscending => |a: &Entry, b: &Entry| a.or.cmp(&b.or),
-            Sort::OpeningDescending => |a: &Entry, b: &Entry| b.or.cmp(&a.or),
-            Sort::ClosingAscending => |a: &Entry, b: &Entry| a.cr.cmp(&b.cr),
-            Sort::ClosingDescending => |a: &Entry, b: &Entry| b.cr.cmp(&a.cr),
-        });
+        let order = TextOrder::new(&self.dictionaries);
+
+        self.entries.sort_by(|a, b| sort.compare(a, b, &order));
+
+        self.apply_filters();
+    }
+
+    /// Group the entries by the family of their branch, in the order of
+    /// [`BranchFamilies::get_families`]. Entries keep their order within each family,
+    /// so this is called after [`Dataset::sort`].
+    pub fn group_by_family(&mut self) {
+        self.entries.sort_by_key(|entry| entry.family);
+        self.apply_filters();
+    }
+
+    /// Recompute which entries match the filters.
+    ///
+    /// This must be called after modifying the filters from [`Dataset::get_filters_mut`].
+    pub fn apply_filters(&mut self) {
+        self.filtered = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| self.filters.matches(entry))
+            .map(|(index, _)| index)
+            .collect();
+    }
+
+    /// Get the loaded round numbers, in the same order as [`Entry::rounds`].
+    pub fn get_rounds(&self) -> &[u8] {
+        &self.rounds
     }
 
-    pub fn get_filters(&mut self) -> &mut Filters {
+    pub fn get_filters(&self) -> &Filters {
+        &self.filters
+    }
+
+    pub fn get_filters_mut(&mut self) -> &mut Filters {
         &mut self.filters
     }
 
-    pub fn get_entries(&self) -> EntryIterator {
-        EntryIterator::new(&self.filters, &self.entries)
+    /// Get the dictionaries which the ids in every [`Entry`] refer to.
+    pub fn get_dictionaries(&self) -> &Dictionaries {
+        &self.dictionaries
+    }
+
+    /// Get the filters along with the dictionaries naming the values they select.
+    pub fn get_filters_with_dictionaries(&mut self) -> (&mut Filters, &Dictionaries) {
+        (&mut self.filters, &self.dictionaries)
+    }
+
+    /// Get the entries matching the filters, as of the last [`Dataset::apply_filters`].
+    pub fn get_entries(&self) -> EntryIterator<'_> {
+        EntryIterator::new(&self.entries, &self.filtered)
+    }
+
+    /// Get the number of entries matching the filters.
+    pub fn get_entry_count(&self) -> usize {
+        self.filtered.len()
+    }
+
+    /// Get the `index`-th entry matching the filters.
+    pub fn get_entry(&self, index: usize) -> Option<&Entry> {
+        self.filtered.get(index).map(|&index| &self.entries[index])
+    }
+
+    /// Run a read-only SQL statement against the loaded dataset.
+    ///
+    /// When several rounds are loaded, this is the last of them.
+    pub fn query(&self, sql: &str) -> Result<QueryResult> {
+        let connection = self.connection.as_ref().ok_or(Error::NotLoaded)?;
+
+        Ok(run_query(connection, sql)?)
+    }
+
+    /// Attach another dataset for use in queries, under the alias from [`get_alias`].
+    ///
+    /// Does nothing if it is already attached.
+    pub fn attach(&mut self, options: &Options, path: &Path) -> Result<()> {
+        let connection = self.connection.as_ref().ok_or(Error::NotLoaded)?;
+
+        if get_alias(options).is_some_and(|alias| self.attached.contains(&alias)) {
+            return Ok(());
+        }
+
+        self.attached.push(attach(connection, options, path)?);
+
+        Ok(())
+    }
+
+    /// Get the aliases of every attached dataset.
+    pub fn get_attached(&self) -> &[String] {
+        &self.attached
+    }
+
+    /// Predict which of the filtered entries `candidate` could get, in the order of `sort`.
+    /// Each [`Prediction::index`] is for [`Dataset::get_entry`].
+    ///
+    /// The entries should already be [sorted](Dataset::sort) by `sort`, so the predictions
+    /// are only sorted again when it has [columns of predictions](SortColumn::is_predicted).
+    pub fn predict(&self, candidate: &Candidate, sort: &Sort) -> Vec<Prediction> {
+        let mut predictions = predict(candidate, &self.dictionaries, self.get_entries());
+
+        if sort.has_predicted() {
+            let order = TextOrder::new(&self.dictionaries);
+            let entries: Vec<&Entry> = self.get_entries().collect();
+
+            predictions.sort_by(|a, b| sort.compare_predictions(a, b, &entries, &order));
+        }
+
+        predictions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Rank, RankRange};
+
+    #[test]
+    fn dataset_joins_rounds() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options {
+            year: Some(2024),
+            round: None,
+            all_rounds: true,
+        };
+
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let mut first = Dataset::default();
+        let first_options = Options::new(2024, 1);
+        first
+            .load(&first_options, &catalog.get_paths(&first_options))
+            .unwrap();
+
+        assert_eq!(dataset.get_rounds(), [1, 2, 3, 4, 5]);
+        assert!(dataset.entries.len() >= first.entries.len());
+
+        // Every row of the first round is joined with its ranks.
+        let joined: FxHashMap<EntryKey, &Entry> = dataset
+            .entries
+            .iter()
+            .map(|entry| (dataset.dictionaries.get_key(entry), entry))
+            .collect();
+
+        for entry in &first.entries {
+            let joined = joined[&first.dictionaries.get_key(entry)];
+
+            assert_eq!(joined.rounds.len(), 5);
+            assert_eq!(joined.rounds[0], entry.rounds[0]);
+        }
+    }
+
+    #[test]
+    fn dataset_filters_ranks_per_domain() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options::new(2024, 5);
+
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let filters = dataset.get_filters_mut();
+
+        assert!(!filters.advanced.is_empty());
+        assert!(!filters.main.is_empty());
+        assert!(filters.advanced.cr_bounds.end < filters.main.cr_bounds.end);
+
+        // Narrowing the JEE Advanced ranks leaves JEE Main rows alone.
+        filters.advanced.cr = RankRange::new(0, 100);
+
+        // Filters are only applied when asked to.
+        assert_eq!(dataset.get_entry_count(), dataset.entries.len());
+
+        dataset.apply_filters();
+
+        let total = dataset.entries.len();
+        let main = dataset
+            .entries
+            .iter()
+            .filter(|entry| entry.domain == RankDomain::Main)
+            .count();
+        let filtered = dataset.get_entries().collect::<Vec<_>>();
+
+        assert!(filtered.len() > main && filtered.len() < total);
+        assert!(
+            filtered
+                .iter()
+                .filter(|entry| entry.domain == RankDomain::Advanced)
+                .all(|entry| entry.cr <= Rank::Regular(100))
+        );
+    }
+
+    #[test]
+    fn dataset_sorts_and_filters_preparatory_ranks() {
+        let root = std::env::temp_dir().join(format!("orcr-preparatory-{}", std::process::id()));
+        let options = Options::new(2030, 1);
+        let path = options.get_db_path(&root).unwrap();
+
+        crate::parse_html(include_str!("../../tests/fixtures/orcr.html"))
+            .unwrap()
+            .write(&path)
+            .unwrap();
+
+        let mut dataset = Dataset::default();
+        dataset.load(&options, &[(1, &path)]).unwrap();
+        std::fs::remove_dir_all(root).unwrap();
+
+        assert!(dataset.get_filters().main.has_preparatory);
+
+        // Preparatory ranks come after every regular rank
+        dataset.sort(&Sort::default());
+
+        let closing: Vec<Rank> = dataset.get_entries().map(|entry| entry.cr).collect();
+
+        assert!(closing.is_sorted());
+        assert_eq!(
+            closing[closing.len() - 2..],
+            [Rank::Preparatory(38), Rank::Preparatory(127)]
+        );
+
+        // They are shown regardless of the ranges, unless hidden
+        let filters = dataset.get_filters_mut();
+
+        for ranks in [&mut filters.advanced, &mut filters.main] {
+            ranks.cr = RankRange::new(0, 1);
+        }
+
+        dataset.apply_filters();
+        assert_eq!(dataset.get_entry_count(), 2);
+
+        dataset.get_filters_mut().main.cr_preparatory = false;
+        dataset.apply_filters();
+
+        let closing: Vec<Rank> = dataset.get_entries().map(|entry| entry.cr).collect();
+
+        assert_eq!(closing, [Rank::Preparatory(38)]);
+    }
+
+    #[test]
+    fn dataset_selects_institutes_across_years() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let dhanbad = find_institute("Indian School of Mines Dhanbad").unwrap();
+
+        // Selected in 2016, under its former name
+        let options = Options::new(2016, 6);
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let (filters, dictionaries) = dataset.get_filters_with_dictionaries();
+        filters.set_institutes(dictionaries, &[dhanbad.id]);
+
+        let ids = dataset
+            .get_filters()
+            .get_institutes(dataset.get_dictionaries());
+
+        assert_eq!(ids, [dhanbad.id]);
+
+        // The same institute is selected in 2024
+        let options = Options::new(2024, 5);
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let (filters, dictionaries) = dataset.get_filters_with_dictionaries();
+        filters.set_institutes(dictionaries, &ids);
+        filters.select_institute_kinds(dictionaries);
+        dataset.apply_filters();
+
+        let dictionaries = dataset.get_dictionaries();
+        let kinds = &dataset.get_filters().institute_kind;
+
+        // Only the type of the selected institute stays selected
+        assert_eq!(kinds.count(), 1);
+        assert_eq!(
+            dictionaries.get_institute_kind(dhanbad.name),
+            Some(RankDomain::ADVANCED_INSTITUTE_TYPE)
+        );
+        assert!(
+            kinds.contains(
+                dictionaries
+                    .institute_kind
+                    .get_id(RankDomain::ADVANCED_INSTITUTE_TYPE)
+                    .unwrap() as usize
+            )
+        );
+
+        assert!(dataset.get_entry_count() > 0);
+        assert!(
+            dataset
+                .get_entries()
+                .all(|entry| dictionaries.institute.get(entry.institute) == dhanbad.name)
+        );
+    }
+
+    #[test]
+    fn dataset_sorts_by_several_columns() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options::new(2024, 1);
+
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let mut sort = Sort::by(SortColumn::Institute, SortDirection::Ascending);
+        sort.add(SortColumn::Closing);
+        sort.add(SortColumn::Closing);
+        dataset.sort(&sort);
+
+        let dictionaries = dataset.get_dictionaries();
+        let rows: Vec<(String, Rank)> = dataset
+            .get_entries()
+            .map(|entry| {
+                (
+                    dictionaries.institute.get(entry.institute).to_lowercase(),
+                    entry.cr,
+                )
+            })
+            .collect();
+
+        // Institutes alphabetically, with closing ranks descending within each
+        assert!(rows.is_sorted_by(|a, b| a.0 < b.0 || (a.0 == b.0 && a.1 >= b.1)));
+
+        // Equal keys are always in the same order
+        let keys = |dataset: &Dataset| -> Vec<EntryKey> {
+            dataset
+                .get_entries()
+                .map(|entry| dataset.get_dictionaries().get_key(entry))
+                .collect()
+        };
+        let first = keys(&dataset);
+
+        dataset.sort(&Sort::by(SortColumn::Branch, SortDirection::Descending));
+        dataset.sort(&sort);
+
+        assert_eq!(keys(&dataset), first);
+    }
+
+    #[test]
+    fn dataset_filters_eligible_quotas() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options::new(2024, 5);
+
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let get_rows = |dataset: &Dataset| -> Vec<(String, String)> {
+            let dictionaries = dataset.get_dictionaries();
+
+            dataset
+                .get_entries()
+                .map(|entry| {
+                    (
+                        dictionaries.quota.get(entry.quota).to_string(),
+                        dictionaries.institute.get(entry.institute).to_string(),
+                    )
+                })
+                .collect()
+        };
+
+        let (filters, dictionaries) = dataset.get_filters_with_dictionaries();
+        filters.set_home_state(dictionaries, Some("Kerala"));
+        filters.eligible_quotas = true;
+        dataset.apply_filters();
+
+        let rows = get_rows(&dataset);
+
+        // Home state seats only in Kerala, and other state seats everywhere else
+        assert!(rows.iter().any(|(quota, _)| quota == "HS"));
+        assert!(rows.iter().all(|(quota, institute)| match quota.as_str() {
+            "AI" => true,
+            "HS" => get_state(institute) == Some("Kerala"),
+            "OS" => get_state(institute) != Some("Kerala"),
+            _ => false,
+        }));
+
+        // Without a home state, only other state and all India seats
+        let (filters, dictionaries) = dataset.get_filters_with_dictionaries();
+        filters.set_home_state(dictionaries, None);
+        dataset.apply_filters();
+
+        assert!(
+            get_rows(&dataset)
+                .iter()
+                .all(|(quota, _)| quota == "AI" || quota == "OS")
+        );
+
+        // Special quotas for candidates from their states
+        let (filters, dictionaries) = dataset.get_filters_with_dictionaries();
+        filters.set_home_state(dictionaries, Some("Goa"));
+        dataset.apply_filters();
+
+        assert!(get_rows(&dataset).iter().any(|(quota, _)| quota == "GO"));
+    }
+
+    #[test]
+    fn dataset_keeps_previous_on_error() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options::new(2024, 5);
+
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let count = dataset.get_entry_count();
+
+        // Options without datasets
+        assert!(matches!(
+            dataset.load(&Options::default(), &[]),
+            Err(Error::InvalidOptions(_))
+        ));
+
+        // Missing and invalid files
+        let missing = Options::new(2024, 9);
+
+        assert!(matches!(
+            dataset.load(&missing, &[(9, Path::new("db/2024/data-2024-9.db"))]),
+            Err(Error::Io { .. })
+        ));
+        assert!(
+            dataset
+                .load(&missing, &[(9, Path::new("Cargo.toml"))])
+                .is_err()
+        );
+
+        assert!(dataset.is_loaded());
+        assert_eq!(dataset.get_entry_count(), count);
     }
 }
@@ -1,44 +1,94 @@
-use std::{ops::RangeInclusive, path::PathBuf};
+use crate::{DEFAULT_ROOT, Error, Result};
+use std::path::{Path, PathBuf};
 
-#[derive(Default, PartialEq, Eq, Clone, Copy)]
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
 pub struct Options {
     pub year: Option<u16>,
     pub round: Option<u8>,
+
+    /// Whether every round of `year` is selected, instead of only `round`.
+    pub all_rounds: bool,
 }
 
 impl Options {
-    pub fn get_valid_years() -> RangeInclusive<u16> {
-        2016..=2024
+    pub fn new(year: u16, round: u8) -> Self {
+        Self {
+            year: Some(year),
+            round: Some(round),
+            all_rounds: false,
+        }
     }
 
-    pub fn get_valid_rounds(year: Option<u16>) -> Option<RangeInclusive<u8>> {
-        year.map(|year| match year {
-            2016 => 1..=6,
-            2017 => 1..=7,
-            2018 => 1..=7,
-            2019 => 1..=7,
-            2020 => 1..=6,
-            2021 => 1..=6,
-            2022 => 1..=6,
-            2023 => 1..=6,
-            2024 => 1..=5,
-
-            _ => panic!("Invalid year: {year}."),
-        })
+    /// Parse a file name following the `data-<year>-<round>.db` format.
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let (year, round) = name
+            .strip_prefix("data-")?
+            .strip_suffix(".db")?
+            .split_once('-')?;
+
+        Some(Self::new(year.parse().ok()?, round.parse().ok()?))
     }
 
     pub fn is_complete(&self) -> bool {
-        self.year.is_some() && self.round.is_some()
+        self.year.is_some() && (self.all_rounds || self.round.is_some())
+    }
+
+    /// Get the path of the selected dataset under the default root.
+    ///
+    /// Fails with [`Error::InvalidOptions`] unless both a year and a round are selected.
+    pub fn into_db_path(&self) -> Result<PathBuf> {
+        self.get_db_path(Path::new(DEFAULT_ROOT))
+    }
+
+    /// Get the path of the selected dataset under `root`.
+    ///
+    /// Fails with [`Error::InvalidOptions`] unless both a year and a round are selected.
+    pub fn get_db_path(&self, root: &Path) -> Result<PathBuf> {
+        let (Some(year), Some(round)) = (self.year, self.round) else {
+            return Err(Error::InvalidOptions(*self));
+        };
+
+        Ok(root
+            .join(year.to_string())
+            .join(format!("data-{year}-{round}.db")))
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_from_file_name() {
+        // Valid
+        assert_eq!(
+            Options::from_file_name("data-2016-1.db"),
+            Some(Options::new(2016, 1))
+        );
+        assert_eq!(
+            Options::from_file_name("data-2025-12.db"),
+            Some(Options::new(2025, 12))
+        );
+
+        // Invalid
+        assert!(Options::from_file_name("data-2016.db").is_none());
+        assert!(Options::from_file_name("data-2016-1.sqlite").is_none());
+        assert!(Options::from_file_name("info-2016-1.db").is_none());
+        assert!(Options::from_file_name("data-20x6-1.db").is_none());
+        assert!(Options::from_file_name("data-2016-1-2.db").is_none());
+    }
+
+    #[test]
+    fn options_into_db_path() {
+        assert_eq!(
+            Options::new(2016, 1).into_db_path().unwrap(),
+            PathBuf::from("db/2016/data-2016-1.db")
+        );
 
-    pub fn into_db_path(&self) -> PathBuf {
-        PathBuf::new()
-            .join("db")
-            .join(self.year.unwrap().to_string())
-            .join(format!(
-                "data-{}-{}.db",
-                self.year.unwrap(),
-                self.round.unwrap()
-            ))
+        // Incomplete
+        assert!(matches!(
+            Options::default().into_db_path(),
+            Err(Error::InvalidOptions(_))
+        ));
     }
 }
@@ -0,0 +1,493 @@
+use crate::{
+    Dictionaries, Entry, get_state,
+    types::{Rank, RankDomain},
+};
+use std::fmt::Display;
+
+/// How far past the closing rank a candidate can be while
+/// still having a chance, as a fraction of the closing rank.
+pub const REACH_MARGIN: f64 = 0.1;
+
+/// The seat categories a candidate can belong to.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Category {
+    #[default]
+    Open,
+    Ews,
+    ObcNcl,
+    Sc,
+    St,
+}
+
+impl Category {
+    pub fn as_vec() -> Vec<Self> {
+        vec![
+            Category::Open,
+            Category::Ews,
+            Category::ObcNcl,
+            Category::Sc,
+            Category::St,
+        ]
+    }
+
+    /// The seat type for this category, as it appears in datasets.
+    pub fn as_seat_type(&self) -> &'static str {
+        match self {
+            Category::Open => "OPEN",
+            Category::Ews => "EWS",
+            Category::ObcNcl => "OBC-NCL",
+            Category::Sc => "SC",
+            Category::St => "ST",
+        }
+    }
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_seat_type())
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Gender {
+    #[default]
+    Male,
+    Female,
+}
+
+impl Gender {
+    pub fn as_vec() -> Vec<Self> {
+        vec![Gender::Male, Gender::Female]
+    }
+}
+
+impl Display for Gender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Gender::Male => write!(f, "Male"),
+            Gender::Female => write!(f, "Female"),
+        }
+    }
+}
+
+/// A candidate's ranks in a single exam. Ranks which
+/// don't apply to the candidate are left as [`None`].
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExamRanks {
+    /// Rank in the common rank list.
+    pub crl: Option<u32>,
+    /// Rank within the candidate's category.
+    pub category: Option<u32>,
+    /// Rank within the common rank list for PwD candidates.
+    pub crl_pwd: Option<u32>,
+    /// Rank within the candidate's category for PwD candidates.
+    pub category_pwd: Option<u32>,
+}
+
+/// Everything about a candidate that decides which seats they can get.
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct Candidate {
+    pub main: ExamRanks,
+    pub advanced: ExamRanks,
+    pub category: Category,
+    pub gender: Gender,
+    pub pwd: bool,
+    pub home_state: Option<String>,
+}
+
+impl Candidate {
+    /// Get the rank the candidate would be compared with for `seat_type`,
+    /// or [`None`] if they aren't eligible for it.
+    fn get_seat_rank(&self, ranks: &ExamRanks, seat_type: &str) -> Option<u32> {
+        let (category, pwd) = match seat_type.strip_suffix(" (PwD)") {
+            Some(category) => (category, true),
+            None => (seat_type, false),
+        };
+
+        if pwd && !self.pwd {
+            return None;
+        }
+
+        match (
+            category == "OPEN",
+            category == self.category.as_seat_type(),
+            pwd,
+        ) {
+            (true, _, false) => ranks.crl,
+            (true, _, true) => ranks.crl_pwd,
+            (false, true, false) => ranks.category,
+            (false, true, true) => ranks.category_pwd,
+            (false, false, _) => None,
+        }
+    }
+
+    fn is_gender_eligible(&self, gender: &str) -> bool {
+        // Only female-only seats are restricted.
+        !gender.starts_with("Female-only") || self.gender == Gender::Female
+    }
+
+    /// Check whether the candidate can take seats in `quota` at `institute`.
+    pub fn is_quota_eligible(&self, quota: &str, institute: &str) -> bool {
+        is_quota_eligible(quota, institute, self.home_state.as_deref())
+    }
+
+    /// Get the rank the candidate would be compared with for `entry`,
+    /// or [`None`] if they aren't eligible for it.
+    ///
+    /// `dictionaries` must be the ones the ids in `entry` refer to.
+    pub fn get_rank(&self, entry: &Entry, dictionaries: &Dictionaries) -> Option<u32> {
+        if !self.is_gender_eligible(dictionaries.gender.get(entry.gender))
+            || !self.is_quota_eligible(
+                dictionaries.quota.get(entry.quota),
+                dictionaries.institute.get(entry.institute),
+            )
+        {
+            return None;
+        }
+
+        let ranks = match entry.domain {
+            RankDomain::Advanced => &self.advanced,
+            RankDomain::Main => &self.main,
+        };
+
+        self.get_seat_rank(ranks, dictionaries.seat_type.get(entry.seat_type))
+    }
+}
+
+/// Check whether a candidate domiciled in `home_state` can take seats in `quota` at `institute`.
+///
+/// Home state seats go to candidates from the institute's state, and other state seats
+/// to everyone else. The Goa, Jammu and Kashmir, Ladakh and Andhra Pradesh quotas are only
+/// for candidates from those states, and all India seats are open to everyone.
+pub fn is_quota_eligible(quota: &str, institute: &str, home_state: Option<&str>) -> bool {
+    let from = |state| home_state == Some(state);
+
+    match quota {
+        "AI" => true,
+        "HS" => home_state.is_some() && home_state == get_state(institute),
+        "OS" => home_state.is_none() || home_state != get_state(institute),
+        "GO" => from("Goa"),
+        "JK" => from("Jammu and Kashmir"),
+        "LA" => from("Ladakh"),
+        "AP" => from("Andhra Pradesh"),
+        _ => false,
+    }
+}
+
+/// How likely a candidate is to get a seat.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Chance {
+    /// The rank is comfortably within the closing rank in every round.
+    Safe,
+    /// The rank is within the closing rank in at least one round.
+    Moderate,
+    /// The rank is within [`REACH_MARGIN`] of the closing rank.
+    Reach,
+}
+
+impl Chance {
+    /// Classify `rank` against the closing ranks of `entry` across its rounds.
+    pub fn classify(rank: u32, entry: &Entry) -> Option<Self> {
+        let closing = entry.rounds.iter().flatten().map(|ranks| ranks.cr);
+        let lowest = get_limit(closing.clone().min().unwrap_or(entry.cr));
+        let highest = get_limit(closing.max().unwrap_or(entry.cr));
+        let rank = rank as f64;
+
+        if rank <= lowest * (1.0 - REACH_MARGIN) {
+            Some(Self::Safe)
+        } else if rank <= highest {
+            Some(Self::Moderate)
+        } else if rank <= highest * (1.0 + REACH_MARGIN) {
+            Some(Self::Reach)
+        } else {
+            None
+        }
+    }
+}
+
+/// Get a closing rank as a limit for the candidate's rank.
+///
+/// Preparatory closing ranks come after every regular rank,
+/// so the candidate's rank is always within them.
+fn get_limit(closing: Rank) -> f64 {
+    closing.get_regular().map_or(f64::INFINITY, f64::from)
+}
+
+impl Display for Chance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Chance::Safe => write!(f, "Safe"),
+            Chance::Moderate => write!(f, "Moderate"),
+            Chance::Reach => write!(f, "Reach"),
+        }
+    }
+}
+
+/// An entry the candidate could plausibly get.
+#[derive(Clone, Copy, Debug)]
+pub struct Prediction {
+    /// The index of the entry among the entries predicted from.
+    pub index: usize,
+    /// The candidate's rank which was compared with the entry.
+    pub rank: u32,
+    pub chance: Chance,
+}
+
+/// Predict which of `entries` the candidate could get, in the order of `entries`.
+pub fn predict<'a>(
+    candidate: &Candidate,
+    dictionaries: &Dictionaries,
+    entries: impl Iterator<Item = &'a Entry>,
+) -> Vec<Prediction> {
+    entries
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let rank = candidate.get_rank(entry, dictionaries)?;
+            let chance = Chance::classify(rank, entry)?;
+
+            Some(Prediction {
+                index,
+                rank,
+                chance,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Catalog, DEFAULT_ROOT, Dataset, Options, Ranks, Sort, SortColumn, SortDirection, SortKey,
+        normalize_name,
+    };
+
+    fn entry(
+        dictionaries: &mut Dictionaries,
+        institute: &str,
+        quota: &str,
+        seat_type: &str,
+        gender: &str,
+        cr: &[u32],
+    ) -> Entry {
+        Entry {
+            institute: dictionaries.institute.intern(institute).unwrap(),
+            institute_kind: 0,
+            branch: dictionaries
+                .branch
+                .intern("Civil Engineering (4 Years, Bachelor of Technology)")
+                .unwrap(),
+            quota: dictionaries.quota.intern(quota).unwrap(),
+            seat_type: dictionaries.seat_type.intern(seat_type).unwrap(),
+            gender: dictionaries.gender.intern(gender).unwrap(),
+            duration: 0,
+            degree: 0,
+            family: 0,
+            domain: RankDomain::Main,
+            or: Rank::Regular(1),
+            cr: Rank::Regular(*cr.last().unwrap()),
+            rounds: cr
+                .iter()
+                .map(|&cr| {
+                    Some(Ranks {
+                        or: Rank::Regular(1),
+                        cr: Rank::Regular(cr),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    fn candidate() -> Candidate {
+        Candidate {
+            main: ExamRanks {
+                crl: Some(10_000),
+                category: Some(2_000),
+                ..Default::default()
+            },
+            advanced: ExamRanks {
+                crl: Some(5_000),
+                ..Default::default()
+            },
+            category: Category::ObcNcl,
+            home_state: Some("Kerala".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn candidate_ranks() {
+        let candidate = candidate();
+        let mut dictionaries = Dictionaries::default();
+        let nit = "National Institute of Technology Calicut";
+
+        // Rank depends on the domain and seat type
+        let open = entry(&mut dictionaries, nit, "HS", "OPEN", "Gender-Neutral", &[1]);
+        let obc = entry(
+            &mut dictionaries,
+            nit,
+            "HS",
+            "OBC-NCL",
+            "Gender-Neutral",
+            &[1],
+        );
+
+        assert_eq!(candidate.get_rank(&open, &dictionaries), Some(10_000));
+        assert_eq!(candidate.get_rank(&obc, &dictionaries), Some(2_000));
+
+        let open = Entry {
+            domain: RankDomain::Advanced,
+            ..open
+        };
+        let obc = Entry {
+            domain: RankDomain::Advanced,
+            ..obc
+        };
+
+        assert_eq!(candidate.get_rank(&open, &dictionaries), Some(5_000));
+        assert_eq!(candidate.get_rank(&obc, &dictionaries), None);
+
+        // Other categories, PwD and female-only seats
+        let sc = entry(&mut dictionaries, nit, "HS", "SC", "Gender-Neutral", &[1]);
+        let pwd = entry(
+            &mut dictionaries,
+            nit,
+            "HS",
+            "OPEN (PwD)",
+            "Gender-Neutral",
+            &[1],
+        );
+        let female = entry(
+            &mut dictionaries,
+            nit,
+            "HS",
+            "OPEN",
+            "Female-only (including Supernumerary)",
+            &[1],
+        );
+
+        assert_eq!(candidate.get_rank(&sc, &dictionaries), None);
+        assert_eq!(candidate.get_rank(&pwd, &dictionaries), None);
+        assert_eq!(candidate.get_rank(&female, &dictionaries), None);
+
+        // Quotas
+        let os = entry(&mut dictionaries, nit, "OS", "OPEN", "Gender-Neutral", &[1]);
+        let other_hs = entry(
+            &mut dictionaries,
+            "National Institute of Technology Goa",
+            "HS",
+            "OPEN",
+            "NA",
+            &[1],
+        );
+        let other_os = entry(
+            &mut dictionaries,
+            "National Institute of Technology Goa",
+            "OS",
+            "OPEN",
+            "NA",
+            &[1],
+        );
+
+        assert_eq!(candidate.get_rank(&os, &dictionaries), None);
+        assert_eq!(candidate.get_rank(&other_hs, &dictionaries), None);
+        assert_eq!(candidate.get_rank(&other_os, &dictionaries), Some(10_000));
+    }
+
+    #[test]
+    fn chance_classify() {
+        let mut dictionaries = Dictionaries::default();
+        let iit = "Indian Institute  of Technology Madras";
+        let single = entry(
+            &mut dictionaries,
+            iit,
+            "AI",
+            "OPEN",
+            "Gender-Neutral",
+            &[1000],
+        );
+
+        assert_eq!(Chance::classify(500, &single), Some(Chance::Safe));
+        assert_eq!(Chance::classify(950, &single), Some(Chance::Moderate));
+        assert_eq!(Chance::classify(1050, &single), Some(Chance::Reach));
+        assert_eq!(Chance::classify(2000, &single), None);
+
+        // Across rounds
+        let rounds = entry(
+            &mut dictionaries,
+            iit,
+            "AI",
+            "OPEN",
+            "Gender-Neutral",
+            &[800, 1000],
+        );
+
+        assert_eq!(Chance::classify(700, &rounds), Some(Chance::Safe));
+        assert_eq!(Chance::classify(900, &rounds), Some(Chance::Moderate));
+        assert_eq!(Chance::classify(1090, &rounds), Some(Chance::Reach));
+
+        // Preparatory closing ranks are after every regular rank
+        let mut preparatory = entry(
+            &mut dictionaries,
+            iit,
+            "AI",
+            "OPEN (PwD)",
+            "Gender-Neutral",
+            &[800, 30],
+        );
+
+        preparatory.cr = Rank::Preparatory(30);
+        preparatory.rounds[1].as_mut().unwrap().cr = Rank::Preparatory(30);
+
+        assert_eq!(Chance::classify(700, &preparatory), Some(Chance::Safe));
+        assert_eq!(Chance::classify(5000, &preparatory), Some(Chance::Moderate));
+    }
+
+    #[test]
+    fn dataset_predict() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options::new(2024, 5);
+
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let predictions = dataset.predict(&candidate(), &Sort::default());
+
+        assert!(!predictions.is_empty());
+
+        // The order of the entries is kept
+        assert!(predictions.is_sorted_by_key(|prediction| prediction.index));
+
+        // Sorting by chance, and then by the candidate's rank
+        let sort = Sort::with_keys(vec![
+            SortKey {
+                column: SortColumn::Chance,
+                direction: SortDirection::Ascending,
+            },
+            SortKey {
+                column: SortColumn::CandidateRank,
+                direction: SortDirection::Descending,
+            },
+        ]);
+        let sorted = dataset.predict(&candidate(), &sort);
+
+        assert_eq!(sorted.len(), predictions.len());
+        assert!(sorted.is_sorted_by(|a, b| {
+            (a.chance, std::cmp::Reverse(a.rank)) <= (b.chance, std::cmp::Reverse(b.rank))
+        }));
+
+        // The correct rank is used for every institute type
+        let dictionaries = dataset.get_dictionaries();
+
+        for prediction in &predictions {
+            let entry = dataset.get_entry(prediction.index).unwrap();
+            let iit = normalize_name(dictionaries.institute.get(entry.institute))
+                .starts_with("Indian Institute of Technology");
+
+            assert_eq!(iit, entry.domain == RankDomain::Advanced);
+            assert!(get_limit(entry.cr) * (1.0 + REACH_MARGIN) >= prediction.rank as f64);
+        }
+    }
+}
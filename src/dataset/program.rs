@@ -0,0 +1,253 @@
+use crate::normalize_name;
+use std::fmt::Display;
+
+/// The duration given to programs whose branch doesn't state one.
+pub const UNKNOWN_DURATION: &str = "Unknown";
+
+/// The kind of degree a program awards.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Degree {
+    BTech,
+    BSc,
+    BArch,
+    BPlan,
+    BDes,
+    BPharm,
+    /// A bachelor's and a master's degree, such as a B.Tech and M.Tech or a BS-MS.
+    DualDegree,
+    IntegratedMTech,
+    IntegratedMSc,
+    IntegratedBTechMba,
+    Other,
+}
+
+impl Degree {
+    /// Recognize the degree named in a branch, such as `Bachelor of Technology`.
+    pub fn parse(text: &str) -> Self {
+        let text = normalize_name(text).to_lowercase();
+        let contains = |patterns: &[&str]| patterns.iter().any(|pattern| text.contains(pattern));
+
+        if contains(&["dual degree"]) {
+            Self::DualDegree
+        } else if text.starts_with("integrated") {
+            if contains(&["science"]) {
+                Self::IntegratedMSc
+            } else if contains(&["m. tech", "m.tech", "technology"]) {
+                Self::IntegratedMTech
+            } else if contains(&["mba"]) {
+                Self::IntegratedBTechMba
+            } else {
+                Self::Other
+            }
+        } else if contains(&["architecture"]) {
+            Self::BArch
+        } else if contains(&["planning"]) {
+            Self::BPlan
+        } else if contains(&["design"]) {
+            Self::BDes
+        } else if contains(&["pharma"]) {
+            Self::BPharm
+        } else if contains(&["science"]) {
+            Self::BSc
+        } else if contains(&["technology", "b. tech", "b.tech"]) {
+            Self::BTech
+        } else {
+            Self::Other
+        }
+    }
+}
+
+impl Display for Degree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Degree::BTech => write!(f, "B.Tech"),
+            Degree::BSc => write!(f, "B.Sc"),
+            Degree::BArch => write!(f, "B.Arch"),
+            Degree::BPlan => write!(f, "B.Plan"),
+            Degree::BDes => write!(f, "B.Des"),
+            Degree::BPharm => write!(f, "B.Pharm"),
+            Degree::DualDegree => write!(f, "Dual Degree"),
+            Degree::IntegratedMTech => write!(f, "Integrated M.Tech"),
+            Degree::IntegratedMSc => write!(f, "Integrated M.Sc"),
+            Degree::IntegratedBTechMba => write!(f, "Integrated B.Tech and MBA"),
+            Degree::Other => write!(f, "Other"),
+        }
+    }
+}
+
+/// The parts of a branch, such as
+/// `Civil Engineering (4 Years, Bachelor of Technology)`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Program {
+    /// The subject, such as `Civil Engineering`.
+    pub discipline: String,
+    /// The duration in years, if the branch states it.
+    pub duration: Option<u8>,
+    pub degree: Degree,
+}
+
+impl Program {
+    /// Split a branch into its parts. The duration and degree are read from
+    /// the parenthesized text at its end, and the rest is the discipline.
+    pub fn parse(branch: &str) -> Self {
+        let branch = normalize_name(branch);
+
+        let Some((discipline, details)) = split_details(&branch) else {
+            return Self {
+                discipline: branch,
+                duration: None,
+                degree: Degree::Other,
+            };
+        };
+
+        let (duration, degree) = details.split_once(',').unwrap_or(("", details));
+
+        Self {
+            discipline: discipline.trim().into(),
+            duration: duration
+                .trim()
+                .strip_suffix("Years")
+                .and_then(|years| years.trim().parse().ok()),
+            degree: Degree::parse(degree),
+        }
+    }
+
+    /// Get the duration as text, such as `5 Years`, or [`UNKNOWN_DURATION`].
+    pub fn get_duration_text(&self) -> String {
+        match self.duration {
+            Some(years) => format_duration(years),
+            None => UNKNOWN_DURATION.into(),
+        }
+    }
+}
+
+/// Format a duration in years as it is written in branches, such as `5 Years`.
+pub fn format_duration(years: u8) -> String {
+    format!("{years} Years")
+}
+
+/// Split `branch` before the parenthesized text at its end, which
+/// may itself contain parentheses, returning both without the parentheses.
+fn split_details(branch: &str) -> Option<(&str, &str)> {
+    let inner = branch.strip_suffix(')')?;
+    let mut depth = 0;
+
+    for (i, c) in inner.char_indices().rev() {
+        match c {
+            ')' => depth += 1,
+            '(' if depth == 0 => return Some((&inner[..i], &inner[i + 1..])),
+            '(' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Catalog, DEFAULT_ROOT, Dataset, Options};
+
+    #[test]
+    fn program_parse() {
+        assert_eq!(
+            Program::parse("Civil Engineering (4 Years, Bachelor of Technology)"),
+            Program {
+                discipline: "Civil Engineering".into(),
+                duration: Some(4),
+                degree: Degree::BTech,
+            }
+        );
+
+        // Nested parentheses and artifacts
+        assert_eq!(
+            Program::parse(
+                "B. Tech. (Mathematics &amp; Computing) M. Tech. in (Mathematics &amp; Computing) \
+                 (5 Years, B.Tech. + M.Tech./MS (Dual Degree))"
+            ),
+            Program {
+                discipline:
+                    "B. Tech. (Mathematics & Computing) M. Tech. in (Mathematics & Computing)"
+                        .into(),
+                duration: Some(5),
+                degree: Degree::DualDegree,
+            }
+        );
+        assert_eq!(
+            Program::parse(
+                "Computer Science and Engineering (with Specialization of Data Science and \
+                 Artificial Intelligence)\n (4 Years, B. Tech / B. Tech (Hons.))"
+            )
+            .degree,
+            Degree::BTech
+        );
+
+        // Degrees
+        for (details, degree) in [
+            ("5 Years, Bachelor of Architecture", Degree::BArch),
+            ("4 Years, Bachelor of Science", Degree::BSc),
+            (
+                "5 Years, Bachelor of Science and Master of Science (Dual Degree)",
+                Degree::DualDegree,
+            ),
+            (
+                "5 Years, Bachelor and Master of Pharmaceutics(Dual Degree)",
+                Degree::DualDegree,
+            ),
+            (
+                "5 Years, Integrated Master of Science",
+                Degree::IntegratedMSc,
+            ),
+            (
+                "5 Years, Integrated Masters in Technology",
+                Degree::IntegratedMTech,
+            ),
+            (
+                "5 Years, Integrated B. Tech. and M. Tech. /MBA",
+                Degree::IntegratedMTech,
+            ),
+            (
+                "5 Years, Integrated B. Tech. and MBA",
+                Degree::IntegratedBTechMba,
+            ),
+        ] {
+            assert_eq!(
+                Program::parse(&format!("Physics ({details})")).degree,
+                degree
+            );
+        }
+
+        // Without details
+        let program = Program::parse("Physics");
+
+        assert_eq!(program.discipline, "Physics");
+        assert_eq!(program.get_duration_text(), UNKNOWN_DURATION);
+        assert_eq!(program.degree, Degree::Other);
+    }
+
+    #[test]
+    fn programs_cover_bundled_datasets() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+
+        for year in catalog.get_years() {
+            let options = Options {
+                year: Some(year),
+                round: None,
+                all_rounds: true,
+            };
+
+            let mut dataset = Dataset::default();
+            dataset
+                .load(&options, &catalog.get_paths(&options))
+                .unwrap();
+
+            for branch in dataset.get_dictionaries().branch.values() {
+                let program = Program::parse(branch);
+
+                assert!(program.duration.is_some(), "{branch:?}");
+                assert_ne!(program.degree, Degree::Other, "{branch:?}");
+            }
+        }
+    }
+}
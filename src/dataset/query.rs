@@ -0,0 +1,188 @@
+use crate::{Error, Options, Result, open_dataset};
+use rusqlite::{
+    Connection,
+    hooks::{AuthAction, AuthContext, Authorization},
+    types::ValueRef,
+};
+use std::path::Path;
+
+/// The most rows a query result will hold. Any further rows are dropped.
+pub const MAX_QUERY_ROWS: usize = 100_000;
+
+/// The columns and rows returned by a custom SQL query,
+/// with every value converted to text.
+#[derive(Default)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Whether rows past [`MAX_QUERY_ROWS`] were dropped.
+    pub truncated: bool,
+}
+
+/// Get the schema name a dataset is attached under, such as `y2024_r5`.
+pub fn get_alias(options: &Options) -> Option<String> {
+    Some(format!("y{}_r{}", options.year?, options.round?))
+}
+
+/// Run a single read-only statement on `conn`.
+///
+/// Statements which could modify a database fail with
+/// [`rusqlite::Error::InvalidQuery`]. Attaching or detaching databases
+/// isn't allowed either, since datasets are only attached with [`attach`].
+pub fn run_query(conn: &Connection, sql: &str) -> rusqlite::Result<QueryResult> {
+    // SQLite counts `ATTACH` and `DETACH` as read-only, so they are denied as they are prepared.
+    conn.authorizer(Some(|context: AuthContext<'_>| match context.action {
+        AuthAction::Attach { .. } | AuthAction::Detach { .. } => Authorization::Deny,
+        _ => Authorization::Allow,
+    }));
+
+    let result = run_read_only(conn, sql);
+
+    conn.authorizer(None::<fn(AuthContext<'_>) -> Authorization>);
+
+    result
+}
+
+fn run_read_only(conn: &Connection, sql: &str) -> rusqlite::Result<QueryResult> {
+    let mut stmt = conn.prepare(sql)?;
+
+    if !stmt.readonly() {
+        return Err(rusqlite::Error::InvalidQuery);
+    }
+
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let mut result = QueryResult {
+        columns,
+        ..Default::default()
+    };
+
+    let mut rows = stmt.query([])?;
+
+    while let Some(row) = rows.next()? {
+        if result.rows.len() == MAX_QUERY_ROWS {
+            result.truncated = true;
+            break;
+        }
+
+        let values = (0..result.columns.len())
+            .map(|i| row.get_ref(i).map(format_value))
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        result.rows.push(values);
+    }
+
+    Ok(result)
+}
+
+/// Attach the dataset at `path` to `conn`, under the alias for `options`.
+pub fn attach(conn: &Connection, options: &Options, path: &Path) -> Result<String> {
+    let alias = get_alias(options).ok_or(Error::InvalidOptions(*options))?;
+
+    // Only datasets with the expected schema can be queried alongside the loaded one.
+    open_dataset(path)?;
+
+    conn.execute(
+        &format!("ATTACH DATABASE ?1 AS {alias};"),
+        [path.to_string_lossy()],
+    )?;
+
+    Ok(alias)
+}
+
+fn format_value(value: ValueRef) -> String {
+    match value {
+        ValueRef::Null => "NULL".into(),
+        ValueRef::Integer(value) => value.to_string(),
+        ValueRef::Real(value) => value.to_string(),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into(),
+        ValueRef::Blob(blob) => format!("<{} bytes>", blob.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Catalog, DEFAULT_ROOT};
+    use rusqlite::OpenFlags;
+
+    fn open(catalog: &Catalog, options: &Options) -> Connection {
+        let path = catalog.get_path(options).unwrap();
+
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap()
+    }
+
+    #[test]
+    fn query_returns_columns_and_rows() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let conn = open(&catalog, &Options::new(2024, 5));
+
+        let result = run_query(
+            &conn,
+            "SELECT quota, COUNT(*) AS total FROM data GROUP BY quota ORDER BY quota;",
+        )
+        .unwrap();
+
+        assert_eq!(result.columns, ["quota", "total"]);
+        assert_eq!(result.rows.len(), 6);
+        assert_eq!(result.rows[0][0], "AI");
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn query_rejects_invalid_statements() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let conn = open(&catalog, &Options::new(2024, 5));
+
+        // Syntax errors
+        assert!(run_query(&conn, "SELEC * FROM data;").is_err());
+        assert!(run_query(&conn, "SELECT * FROM missing;").is_err());
+
+        // Writes
+        assert!(matches!(
+            run_query(&conn, "DELETE FROM data;"),
+            Err(rusqlite::Error::InvalidQuery)
+        ));
+
+        // Multiple statements
+        assert!(run_query(&conn, "SELECT 1; SELECT 2;").is_err());
+
+        // Attaching and detaching, which SQLite counts as read-only
+        let options = Options::new(2024, 1);
+        let path = catalog.get_path(&options).unwrap();
+
+        assert!(run_query(&conn, "ATTACH DATABASE 'x' AS y;").is_err());
+        assert!(
+            run_query(
+                &conn,
+                &format!("ATTACH DATABASE '{}' AS y;", path.display())
+            )
+            .is_err()
+        );
+        assert!(run_query(&conn, "SELECT * FROM y.data;").is_err());
+
+        // Datasets can still be attached with their schema checked, but not detached
+        assert!(attach(&conn, &options, path).is_ok());
+        assert!(run_query(&conn, "DETACH DATABASE y2024_r1;").is_err());
+        assert!(run_query(&conn, "SELECT COUNT(*) FROM y2024_r1.data;").is_ok());
+    }
+
+    #[test]
+    fn query_across_attached_datasets() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let conn = open(&catalog, &Options::new(2024, 5));
+
+        let options = Options::new(2024, 1);
+        let alias = attach(&conn, &options, catalog.get_path(&options).unwrap()).unwrap();
+
+        assert_eq!(alias, "y2024_r1");
+
+        let result = run_query(
+            &conn,
+            "SELECT COUNT(*) FROM data JOIN y2024_r1.data AS first \
+             USING (institute, branch, quota, seatType, gender);",
+        )
+        .unwrap();
+
+        assert_ne!(result.rows[0][0], "0");
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{Dictionaries, Dictionary, Entry, find_institute, types::BitSet};
+use std::ops::Range;
+
+/// Words left out of abbreviations, such as the `of` in `IIT` for
+/// `Indian Institute of Technology`. Raw names may still contain `&amp;`.
+const STOP_WORDS: [&str; 6] = ["of", "and", "in", "for", "the", "amp"];
+
+/// The shortest search term which still matches a word with a typo in it.
+const MIN_FUZZY_LEN: usize = 5;
+
+/// A column of the table which is searched.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SearchField {
+    Institute,
+    Branch,
+    Quota,
+    SeatType,
+}
+
+/// A word of the search, with the values of every searched column it matches.
+#[derive(Clone)]
+struct Term {
+    text: String,
+    institute: BitSet,
+    branch: BitSet,
+    quota: BitSet,
+    seat_type: BitSet,
+}
+
+/// A free-text search across the institute, branch, quota and seat type of entries.
+///
+/// Every word of the search must match at least one of the columns. Institutes and
+/// branches are also matched by their abbreviations, such as `iitb` or `cse`, by the
+/// short names of bundled institutes, such as `nit trichy`, and despite a typo in longer words.
+#[derive(Clone, Default)]
+pub struct Search {
+    query: String,
+    terms: Vec<Term>,
+}
+
+impl Search {
+    /// Parse `query` into terms, finding the values of `dictionaries` each of them matches.
+    pub fn new(query: &str, dictionaries: &Dictionaries) -> Self {
+        let terms = query
+            .split_whitespace()
+            .map(|text| {
+                let text = text.to_lowercase();
+
+                Term {
+                    institute: match_institutes(&dictionaries.institute, &text),
+                    branch: match_values(&dictionaries.branch, |_, value| {
+                        !find(value, &text, true).is_empty()
+                    }),
+                    quota: match_values(&dictionaries.quota, |_, value| {
+                        !find(value, &text, false).is_empty()
+                    }),
+                    seat_type: match_values(&dictionaries.seat_type, |_, value| {
+                        !find(value, &text, false).is_empty()
+                    }),
+                    text,
+                }
+            })
+            .collect();
+
+        Self {
+            query: query.into(),
+            terms,
+        }
+    }
+
+    pub fn get_query(&self) -> &str {
+        &self.query
+    }
+
+    /// Whether the search has no terms, and so matches every entry.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, entry: &Entry) -> bool {
+        self.terms.iter().all(|term| {
+            term.institute.contains(entry.institute as usize)
+                || term.branch.contains(entry.branch as usize)
+                || term.quota.contains(entry.quota as usize)
+                || term.seat_type.contains(entry.seat_type as usize)
+        })
+    }
+
+    /// Get the byte ranges of `text`, a value of `field`, which match the search,
+    /// sorted and without overlaps.
+    pub fn get_highlights(&self, field: SearchField, text: &str) -> Vec<Range<usize>> {
+        let fuzzy = matches!(field, SearchField::Institute | SearchField::Branch);
+
+        let mut ranges: Vec<Range<usize>> = self
+            .terms
+            .iter()
+            .flat_map(|term| find(text, &term.text, fuzzy))
+            .collect();
+
+        ranges.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        merged
+    }
+}
+
+/// Select the values of `dictionary` for which `matches` is true, given their id and value.
+fn match_values(dictionary: &Dictionary, matches: impl Fn(usize, &str) -> bool) -> BitSet {
+    let mut set = BitSet::new(dictionary.len(), false);
+
+    for (id, value) in dictionary.values().iter().enumerate() {
+        set.set(id, matches(id, value));
+    }
+
+    set
+}
+
+/// Select the institutes matching `term`. A term which is exactly the short name of
+/// a bundled institute, ignoring spaces, only abbreviates that institute, so that
+/// `iitb` doesn't also match `IIT BBS` and `IIT BHU`.
+fn match_institutes(dictionary: &Dictionary, term: &str) -> BitSet {
+    let short_names: Vec<Option<String>> = dictionary
+        .values()
+        .iter()
+        .map(|value| find_institute(value).map(|institute| institute.short_name.to_lowercase()))
+        .collect();
+    let compact = |short_name: &str| short_name.split_whitespace().collect::<String>();
+
+    let exact = short_names
+        .iter()
+        .flatten()
+        .any(|short_name| compact(short_name) == term);
+
+    match_values(dictionary, |id, value| {
+        let short_name = short_names[id].as_deref();
+
+        if exact {
+            short_name.is_some_and(|short_name| compact(short_name) == term)
+                || !find(value, term, false).is_empty()
+        } else {
+            short_name.is_some_and(|short_name| {
+                compact(short_name).starts_with(term)
+                    || short_name
+                        .split_whitespace()
+                        .any(|word| word.starts_with(term))
+            }) || !find(value, term, true).is_empty()
+        }
+    })
+}
+
+/// Find where the lowercase `term` matches in `text`, as byte ranges.
+///
+/// It matches anywhere as a substring, ignoring case. If `fuzzy` is set it also matches
+/// the initials of the words in `text`, such as `cse` for `Computer Science and
+/// Engineering`, and words which are a single typo away from it.
+fn find(text: &str, term: &str, fuzzy: bool) -> Vec<Range<usize>> {
+    // ASCII lowercase keeps every byte where it was, so ranges are the same in `text`.
+    let lowercase = text.to_ascii_lowercase();
+
+    let mut ranges: Vec<Range<usize>> = lowercase
+        .match_indices(term)
+        .map(|(start, _)| start..start + term.len())
+        .collect();
+
+    if !fuzzy {
+        return ranges;
+    }
+
+    let words: Vec<Range<usize>> = get_words(text)
+        .filter(|word| !STOP_WORDS.contains(&&lowercase[word.clone()]))
+        .collect();
+
+    let letters = term.chars().count();
+
+    // Abbreviations, matching the initials of the first words
+    let initials: Vec<Range<usize>> = words
+        .iter()
+        .take(letters)
+        .map(|word| {
+            let len = lowercase[word.clone()]
+                .chars()
+                .next()
+                .map_or(0, char::len_utf8);
+
+            word.start..word.start + len
+        })
+        .collect();
+
+    if letters > 1
+        && initials.len() == letters
+        && initials
+            .iter()
+            .map(|initial| lowercase[initial.clone()].chars().next())
+            .eq(term.chars().map(Some))
+    {
+        ranges.extend(initials);
+    }
+
+    // Typos
+    if letters >= MIN_FUZZY_LEN {
+        ranges.extend(words.into_iter().filter(|word| {
+            let word = &lowercase[word.clone()];
+            let prefix: String = word.chars().take(letters).collect();
+
+            edit_distance(word, term) <= 1 || edit_distance(&prefix, term) <= 1
+        }));
+    }
+
+    ranges
+}
+
+/// Get the byte ranges of the alphanumeric words in `text`.
+fn get_words(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut start = None;
+
+    text.char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+        .filter_map(move |(i, c)| match (c.is_alphanumeric(), start) {
+            (true, None) => {
+                start = Some(i);
+                None
+            }
+            (false, Some(word)) => {
+                start = None;
+                Some(word..i)
+            }
+            _ => None,
+        })
+}
+
+/// Get the number of characters which must be inserted, removed
+/// or replaced to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+
+        for (j, b) in b.iter().enumerate() {
+            current[j + 1] = (previous[j] + usize::from(a != *b))
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Catalog, DEFAULT_ROOT, Dataset, Options};
+
+    fn spans(ranges: Vec<Range<usize>>) -> Vec<(usize, usize)> {
+        ranges
+            .into_iter()
+            .map(|range| (range.start, range.end))
+            .collect()
+    }
+
+    #[test]
+    fn search_find() {
+        let text = "Computer Science and Engineering (4 Years, Bachelor of Technology)";
+
+        // Substrings, ignoring case
+        assert_eq!(spans(find(text, "science", false)), [(9, 16)]);
+        assert!(find(text, "cse", false).is_empty());
+
+        // Abbreviations skip stop words
+        assert_eq!(spans(find(text, "cse", true)), [(0, 1), (9, 10), (21, 22)]);
+        assert_eq!(
+            spans(find("Indian Institute of Technology Bombay", "iitb", true)),
+            [(0, 1), (7, 8), (20, 21), (31, 32)]
+        );
+        assert!(find(text, "ece", true).is_empty());
+
+        // Typos in longer words
+        assert_eq!(spans(find(text, "enginering", true)), [(21, 32)]);
+        assert_eq!(spans(find(text, "compter", true)), [(0, 8)]);
+        assert!(find(text, "compter", false).is_empty());
+
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn search_datasets() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options::new(2024, 1);
+
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let mut search = |query: &str| {
+            let (filters, dictionaries) = dataset.get_filters_with_dictionaries();
+            filters.set_search(dictionaries, query);
+            dataset.apply_filters();
+
+            let dictionaries = dataset.get_dictionaries();
+            let mut institutes: Vec<&str> = dataset
+                .get_entries()
+                .map(|entry| dictionaries.institute.get(entry.institute))
+                .collect();
+
+            institutes.sort_unstable();
+            institutes.dedup();
+            (
+                dataset.get_entry_count(),
+                institutes.len(),
+                institutes.first().map(|name| name.to_string()),
+            )
+        };
+
+        let (count, institutes, institute) = search("iitb cse");
+
+        assert!(count > 0);
+        assert_eq!(institutes, 1);
+        assert_eq!(
+            institute.as_deref(),
+            Some("Indian Institute of Technology Bombay")
+        );
+
+        let (count, institutes, institute) = search("nit trichy ece");
+
+        assert!(count > 0);
+        assert_eq!(institutes, 1);
+        assert_eq!(
+            institute.as_deref(),
+            Some("National Institute of Technology, Tiruchirappalli")
+        );
+
+        // Every term must match
+        assert_eq!(search("iitb architecture").0, 0);
+        assert_eq!(search("").0, search("   ").0);
+    }
+
+    #[test]
+    fn search_highlights() {
+        let mut dictionaries = Dictionaries::default();
+        dictionaries.quota.intern("OS").unwrap();
+
+        let search = Search::new("os cse", &dictionaries);
+
+        assert_eq!(
+            spans(search.get_highlights(SearchField::Branch, "Computer Science and Engineering")),
+            [(0, 1), (9, 10), (21, 22)]
+        );
+        assert_eq!(
+            spans(search.get_highlights(SearchField::Quota, "OS")),
+            [(0, 2)]
+        );
+        assert_eq!(
+            spans(search.get_highlights(SearchField::Quota, "CSE")),
+            [(0, 3)]
+        );
+        assert!(
+            search
+                .get_highlights(SearchField::SeatType, "OPEN")
+                .is_empty()
+        );
+    }
+}
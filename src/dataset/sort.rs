@@ -1,37 +1,413 @@
-use std::fmt::Display;
+use crate::{Dictionaries, Dictionary, Entry, Prediction, Ranks, types::Rank};
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-pub enum Sort {
-    OpeningAscending,
-    OpeningDescending,
-    ClosingAscending,
-    ClosingDescending,
+/// A column of the table which entries can be sorted by.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SortColumn {
+    Institute,
+    Branch,
+    Family,
+    Quota,
+    SeatType,
+    Gender,
+    Exam,
+    /// The opening rank from the last loaded round the entry appears in.
+    Opening,
+    /// The closing rank from the last loaded round the entry appears in.
+    Closing,
+    /// The opening rank in the round at this index of [`Dataset::get_rounds`](crate::Dataset::get_rounds).
+    RoundOpening(usize),
+    /// The closing rank in the round at this index of [`Dataset::get_rounds`](crate::Dataset::get_rounds).
+    RoundClosing(usize),
+    /// The candidate's rank which a [`Prediction`] was compared with.
+    CandidateRank,
+    /// The [chance](crate::Chance) of a [`Prediction`].
+    Chance,
 }
 
-impl Sort {
-    pub fn as_vec() -> Vec<Self> {
-        vec![
-            Sort::OpeningAscending,
-            Sort::OpeningDescending,
-            Sort::ClosingAscending,
-            Sort::ClosingDescending,
-        ]
+impl SortColumn {
+    /// Columns which can be named on the command line, along with their names.
+    pub const NAMED: [(&str, Self); 9] = [
+        ("institute", Self::Institute),
+        ("branch", Self::Branch),
+        ("family", Self::Family),
+        ("quota", Self::Quota),
+        ("seat-type", Self::SeatType),
+        ("gender", Self::Gender),
+        ("exam", Self::Exam),
+        ("or", Self::Opening),
+        ("cr", Self::Closing),
+    ];
+
+    /// Whether the column is only known for predictions. Entries are equal in it.
+    pub fn is_predicted(self) -> bool {
+        matches!(self, Self::CandidateRank | Self::Chance)
     }
 }
 
-impl Default for Sort {
-    fn default() -> Self {
-        Self::ClosingAscending
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    pub fn reverse(self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+
+    /// An arrow pointing up for ascending and down for descending.
+    pub fn get_arrow(self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "⏶",
+            SortDirection::Descending => "⏷",
+        }
     }
 }
 
-impl Display for Sort {
+impl Display for SortDirection {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Sort::OpeningAscending => write!(f, "Ascending (OR)"),
-            Sort::OpeningDescending => write!(f, "Descending (OR)"),
-            Sort::ClosingAscending => write!(f, "Ascending (CR)"),
-            Sort::ClosingDescending => write!(f, "Descending (CR)"),
+            SortDirection::Ascending => write!(f, "Ascending"),
+            SortDirection::Descending => write!(f, "Descending"),
+        }
+    }
+}
+
+/// A column to sort by, in a direction.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SortKey {
+    pub column: SortColumn,
+    pub direction: SortDirection,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    /// Parse a key such as `cr`, `institute-asc` or `or-desc`, which is ascending by default.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let (name, direction) = if let Some(name) = text.strip_suffix("-desc") {
+            (name, SortDirection::Descending)
+        } else {
+            (
+                text.strip_suffix("-asc").unwrap_or(text),
+                SortDirection::Ascending,
+            )
+        };
+
+        SortColumn::NAMED
+            .into_iter()
+            .find(|(column, _)| column.eq_ignore_ascii_case(name))
+            .map(|(_, column)| SortKey { column, direction })
+            .ok_or_else(|| {
+                let names: Vec<&str> = SortColumn::NAMED.iter().map(|(name, _)| *name).collect();
+
+                format!(
+                    "unknown sort column {name:?}, expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// The columns entries are sorted by, from the most significant.
+///
+/// Entries equal in every key are ordered by their institute, branch,
+/// quota, seat type and gender, so the order never depends on the previous one.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Sort {
+    keys: Vec<SortKey>,
+}
+
+impl Default for Sort {
+    /// Closing ranks, ascending.
+    fn default() -> Self {
+        Self::by(SortColumn::Closing, SortDirection::Ascending)
+    }
+}
+
+impl Sort {
+    /// The ties between entries which are equal in every key are broken by these columns.
+    const TIE_BREAKERS: [SortColumn; 5] = [
+        SortColumn::Institute,
+        SortColumn::Branch,
+        SortColumn::Quota,
+        SortColumn::SeatType,
+        SortColumn::Gender,
+    ];
+
+    /// Sort by a single column.
+    pub fn by(column: SortColumn, direction: SortDirection) -> Self {
+        Self {
+            keys: vec![SortKey { column, direction }],
+        }
+    }
+
+    /// Sort by `keys`, or by the [default](Sort::default) if there are none.
+    pub fn with_keys(keys: Vec<SortKey>) -> Self {
+        if keys.is_empty() {
+            Self::default()
+        } else {
+            Self { keys }
+        }
+    }
+
+    pub fn get_keys(&self) -> &[SortKey] {
+        &self.keys
+    }
+
+    /// Get the position of `column` among the keys and its direction, if it is sorted by.
+    pub fn get_key(&self, column: SortColumn) -> Option<(usize, SortDirection)> {
+        self.keys
+            .iter()
+            .position(|key| key.column == column)
+            .map(|position| (position, self.keys[position].direction))
+    }
+
+    /// Sort by `column` alone. Its direction is reversed if
+    /// it was the most significant key, or else ascending.
+    pub fn select(&mut self, column: SortColumn) {
+        let direction = match self.keys.first() {
+            Some(key) if key.column == column => key.direction.reverse(),
+            _ => SortDirection::Ascending,
+        };
+
+        self.keys = vec![SortKey { column, direction }];
+    }
+
+    /// Add `column` as the least significant key, ascending,
+    /// or reverse its direction if it already is a key.
+    pub fn add(&mut self, column: SortColumn) {
+        match self.keys.iter_mut().find(|key| key.column == column) {
+            Some(key) => key.direction = key.direction.reverse(),
+            None => self.keys.push(SortKey {
+                column,
+                direction: SortDirection::Ascending,
+            }),
+        }
+    }
+
+    /// Whether any key is [only known for predictions](SortColumn::is_predicted).
+    pub fn has_predicted(&self) -> bool {
+        self.keys.iter().any(|key| key.column.is_predicted())
+    }
+
+    /// Compare two entries by every key, and then by the tie breakers.
+    pub(crate) fn compare(&self, a: &Entry, b: &Entry, order: &TextOrder) -> Ordering {
+        self.compare_with(a, b, order, |_| Ordering::Equal)
+    }
+
+    /// Compare two predictions for `entries` like [`Sort::compare`],
+    /// but also by the columns only known for predictions.
+    pub(crate) fn compare_predictions(
+        &self,
+        a: &Prediction,
+        b: &Prediction,
+        entries: &[&Entry],
+        order: &TextOrder,
+    ) -> Ordering {
+        self.compare_with(
+            entries[a.index],
+            entries[b.index],
+            order,
+            |column| match column {
+                SortColumn::CandidateRank => a.rank.cmp(&b.rank),
+                SortColumn::Chance => a.chance.cmp(&b.chance),
+                _ => Ordering::Equal,
+            },
+        )
+    }
+
+    /// Compare two entries by every key, using `predicted` for the columns
+    /// only known for predictions, and then by the tie breakers.
+    fn compare_with(
+        &self,
+        a: &Entry,
+        b: &Entry,
+        order: &TextOrder,
+        predicted: impl Fn(SortColumn) -> Ordering,
+    ) -> Ordering {
+        self.keys
+            .iter()
+            .map(|key| {
+                let ordering = if key.column.is_predicted() {
+                    predicted(key.column)
+                } else {
+                    compare_column(key.column, a, b, order)
+                };
+
+                match (key.column, key.direction) {
+                    // Rounds an entry doesn't appear in come last either way.
+                    (SortColumn::RoundOpening(round) | SortColumn::RoundClosing(round), _)
+                        if is_missing(a, round) != is_missing(b, round) =>
+                    {
+                        ordering
+                    }
+                    (_, SortDirection::Ascending) => ordering,
+                    (_, SortDirection::Descending) => ordering.reverse(),
+                }
+            })
+            .chain(
+                Self::TIE_BREAKERS
+                    .iter()
+                    .map(|column| compare_column(*column, a, b, order)),
+            )
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// The alphabetical position of every value of the text columns, indexed by id,
+/// since ids are in the order values were first read.
+pub(crate) struct TextOrder {
+    institute: Vec<u32>,
+    branch: Vec<u32>,
+    family: Vec<u32>,
+    quota: Vec<u32>,
+    seat_type: Vec<u32>,
+    gender: Vec<u32>,
+}
+
+impl TextOrder {
+    pub fn new(dictionaries: &Dictionaries) -> Self {
+        Self {
+            institute: get_order(&dictionaries.institute),
+            branch: get_order(&dictionaries.branch),
+            family: get_order(&dictionaries.family),
+            quota: get_order(&dictionaries.quota),
+            seat_type: get_order(&dictionaries.seat_type),
+            gender: get_order(&dictionaries.gender),
+        }
+    }
+}
+
+/// Get the position of every value in `dictionary` when sorted ignoring case.
+fn get_order(dictionary: &Dictionary) -> Vec<u32> {
+    let mut ids: Vec<usize> = (0..dictionary.len()).collect();
+    ids.sort_by_cached_key(|&id| {
+        let value = &dictionary.values()[id];
+
+        (value.to_lowercase(), value.clone())
+    });
+
+    let mut order = vec![0; ids.len()];
+
+    for (position, id) in ids.into_iter().enumerate() {
+        order[id] = position as u32;
+    }
+
+    order
+}
+
+fn is_missing(entry: &Entry, round: usize) -> bool {
+    entry.rounds.get(round).is_none_or(Option::is_none)
+}
+
+/// Compare two entries by `column` in ascending order.
+fn compare_column(column: SortColumn, a: &Entry, b: &Entry, order: &TextOrder) -> Ordering {
+    let text = |order: &[u32], a: u16, b: u16| order[a as usize].cmp(&order[b as usize]);
+    let round = |round: usize, ranks: fn(&Ranks) -> Rank| {
+        let get = |entry: &Entry| {
+            entry
+                .rounds
+                .get(round)
+                .copied()
+                .flatten()
+                .map(|r| ranks(&r))
+        };
+
+        // Missing rounds come after every rank.
+        match (get(a), get(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (a, b) => a.is_none().cmp(&b.is_none()),
         }
+    };
+
+    match column {
+        SortColumn::Institute => text(&order.institute, a.institute, b.institute),
+        SortColumn::Branch => text(&order.branch, a.branch, b.branch),
+        SortColumn::Family => text(&order.family, a.family, b.family),
+        SortColumn::Quota => text(&order.quota, a.quota, b.quota),
+        SortColumn::SeatType => text(&order.seat_type, a.seat_type, b.seat_type),
+        SortColumn::Gender => text(&order.gender, a.gender, b.gender),
+        SortColumn::Exam => a.domain.cmp(&b.domain),
+        SortColumn::Opening => a.or.cmp(&b.or),
+        SortColumn::Closing => a.cr.cmp(&b.cr),
+        SortColumn::RoundOpening(index) => round(index, |ranks| ranks.or),
+        SortColumn::RoundClosing(index) => round(index, |ranks| ranks.cr),
+        SortColumn::CandidateRank | SortColumn::Chance => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_keys() {
+        let mut sort = Sort::default();
+
+        // Clicking the most significant key reverses it
+        sort.select(SortColumn::Closing);
+        assert_eq!(
+            sort.get_key(SortColumn::Closing),
+            Some((0, SortDirection::Descending))
+        );
+
+        // Other columns replace it
+        sort.select(SortColumn::Institute);
+        assert_eq!(
+            sort,
+            Sort::by(SortColumn::Institute, SortDirection::Ascending)
+        );
+
+        // Adding keys
+        sort.add(SortColumn::Branch);
+        sort.add(SortColumn::Opening);
+        sort.add(SortColumn::Branch);
+
+        assert_eq!(
+            sort.get_key(SortColumn::Branch),
+            Some((1, SortDirection::Descending))
+        );
+        assert_eq!(
+            sort.get_key(SortColumn::Opening),
+            Some((2, SortDirection::Ascending))
+        );
+        assert_eq!(sort.get_key(SortColumn::Closing), None);
+
+        // Selecting drops the other keys
+        sort.select(SortColumn::Opening);
+        assert_eq!(sort.get_keys().len(), 1);
+
+        assert_eq!(Sort::with_keys(Vec::new()), Sort::default());
+    }
+
+    #[test]
+    fn sort_key_parsing() {
+        assert_eq!(
+            "cr-desc".parse(),
+            Ok(SortKey {
+                column: SortColumn::Closing,
+                direction: SortDirection::Descending
+            })
+        );
+        assert_eq!(
+            "Seat-Type".parse(),
+            Ok(SortKey {
+                column: SortColumn::SeatType,
+                direction: SortDirection::Ascending
+            })
+        );
+        assert_eq!(
+            "or-asc".parse::<SortKey>().map(|key| key.column),
+            Ok(SortColumn::Opening)
+        );
+        assert!("rank".parse::<SortKey>().is_err());
     }
 }
@@ -0,0 +1,187 @@
+use crate::{
+    Catalog, EntryKey, Options, Ranks, Result, canonicalize_institute, get_rank_columns,
+    open_dataset,
+};
+use rusqlite::OptionalExtension;
+
+/// Datasets before 2018 don't split seats by gender, and use this
+/// in place of [`NEUTRAL_GENDER`].
+const UNSPLIT_GENDER: &str = "NA";
+
+/// The gender of seats open to everyone, from 2018 on.
+const NEUTRAL_GENDER: &str = "Gender-Neutral";
+
+/// The ranks of a program in the final round of a year.
+pub struct TrendPoint {
+    pub year: u16,
+    pub round: u8,
+
+    /// [`None`] if the program wasn't found in that year's dataset.
+    pub ranks: Option<Ranks>,
+}
+
+/// The opening and closing ranks of a single program over the years.
+pub struct Trend {
+    pub key: EntryKey,
+    pub points: Vec<TrendPoint>,
+}
+
+impl Trend {
+    /// Read `key` from the final round of every year in `catalog`.
+    ///
+    /// The institute is matched under every name it was published under, so renamed
+    /// institutes keep their history. Years where the program doesn't exist (or the
+    /// branch was named differently) are kept as points without ranks.
+    pub fn load(catalog: &Catalog, key: EntryKey) -> Result<Self> {
+        let mut points = Vec::new();
+        let institute = canonicalize_institute(&key.institute);
+
+        for year in catalog.get_years() {
+            let Some(round) = catalog.get_rounds(Some(year)).last() else {
+                continue;
+            };
+
+            let Some(path) = catalog.get_path(&Options::new(year, round)) else {
+                continue;
+            };
+
+            let conn = open_dataset(path)?;
+
+            // Gender-neutral and unsplit seats are matched against each other.
+            let gender = match key.gender.as_str() {
+                NEUTRAL_GENDER => UNSPLIT_GENDER,
+                UNSPLIT_GENDER => NEUTRAL_GENDER,
+                gender => gender,
+            };
+
+            // The names this year's dataset spells the institute with.
+            let names: Vec<String> = conn
+                .prepare("SELECT DISTINCT institute FROM data;")?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?
+                .into_iter()
+                .filter(|name| canonicalize_institute(name) == institute)
+                .collect();
+
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM data WHERE institute = ?1 AND branch = ?2 \
+                 AND quota = ?3 AND seatType = ?4 AND gender IN (?5, ?6);",
+                get_rank_columns(&conn)?
+            ))?;
+            let mut ranks = None;
+
+            for name in &names {
+                ranks = stmt
+                    .query_row(
+                        [
+                            name,
+                            &key.branch,
+                            &key.quota,
+                            &key.seat_type,
+                            &key.gender,
+                            gender,
+                        ],
+                        |row| Ranks::from_row(row, 0),
+                    )
+                    .optional()?;
+
+                if ranks.is_some() {
+                    break;
+                }
+            }
+
+            points.push(TrendPoint { year, round, ranks });
+        }
+
+        Ok(Self { key, points })
+    }
+
+    /// Split the points into runs of consecutive years with ranks,
+    /// so that gaps can be drawn as breaks in the chart.
+    pub fn get_segments(&self) -> Vec<Vec<(u16, Ranks)>> {
+        let mut segments = vec![Vec::new()];
+
+        for point in &self.points {
+            match point.ranks {
+                Some(ranks) => segments.last_mut().unwrap().push((point.year, ranks)),
+                None => segments.push(Vec::new()),
+            }
+        }
+
+        segments.retain(|segment| !segment.is_empty());
+        segments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DEFAULT_ROOT;
+
+    fn key(branch: &str) -> EntryKey {
+        EntryKey {
+            institute: "Indian Institute  of Technology Bhubaneswar".into(),
+            branch: branch.into(),
+            quota: "AI".into(),
+            seat_type: "OPEN".into(),
+            gender: NEUTRAL_GENDER.into(),
+        }
+    }
+
+    #[test]
+    fn trend_reads_every_year() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let trend = Trend::load(
+            &catalog,
+            key("Civil Engineering (4 Years, Bachelor of Technology)"),
+        )
+        .unwrap();
+
+        assert_eq!(trend.points.len(), catalog.get_years().count());
+        assert!(trend.points.iter().all(|point| point.ranks.is_some()));
+        assert_eq!(trend.get_segments().len(), 1);
+    }
+
+    #[test]
+    fn trend_matches_unsplit_genders() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let trend = Trend::load(
+            &catalog,
+            EntryKey {
+                gender: UNSPLIT_GENDER.into(),
+                ..key("Civil Engineering (4 Years, Bachelor of Technology)")
+            },
+        )
+        .unwrap();
+
+        // Opened from a 2016 row, the years with gender-neutral seats are still found
+        assert!(trend.points.iter().any(|point| point.year >= 2018));
+        assert!(trend.points.iter().all(|point| point.ranks.is_some()));
+    }
+
+    #[test]
+    fn trend_follows_renamed_institutes() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let trend = Trend::load(
+            &catalog,
+            EntryKey {
+                institute: "Indian Institute of Technology (ISM) Dhanbad".into(),
+                ..key("Mining Engineering  (4 Years, Bachelor of Technology)")
+            },
+        )
+        .unwrap();
+
+        // Published as the Indian School of Mines in 2016
+        assert!(trend.points.iter().all(|point| point.ranks.is_some()));
+    }
+
+    #[test]
+    fn trend_shows_gaps() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let trend = Trend::load(&catalog, key("Not a real branch")).unwrap();
+
+        assert_eq!(trend.points.len(), catalog.get_years().count());
+        assert!(trend.points.iter().all(|point| point.ranks.is_none()));
+        assert!(trend.get_segments().is_empty());
+    }
+}
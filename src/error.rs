@@ -0,0 +1,106 @@
+use crate::Options;
+use std::{fmt::Display, path::PathBuf};
+
+/// Everything that can go wrong while reading or importing datasets.
+#[derive(Debug)]
+pub enum Error {
+    /// A dataset file couldn't be read.
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Sqlite(rusqlite::Error),
+
+    /// A dataset is missing a table or column the app reads.
+    Schema {
+        path: PathBuf,
+        table: String,
+        column: String,
+    },
+
+    /// The options don't select a dataset which exists.
+    InvalidOptions(Options),
+
+    /// A column has more distinct values than its ids can hold.
+    TooManyValues,
+
+    /// An operation needs a dataset, but none is loaded.
+    NotLoaded,
+
+    /// Loading was cancelled before it finished.
+    Cancelled,
+
+    /// A file being imported doesn't contain the expected data.
+    Import {
+        path: PathBuf,
+        reason: String,
+    },
+
+    /// A file of branch families isn't in the expected format.
+    BranchFamilies {
+        path: PathBuf,
+        reason: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { path, error } => write!(f, "Couldn't read {}: {error}", path.display()),
+            Error::Sqlite(error) => write!(f, "SQLite error: {error}"),
+            Error::Schema {
+                path,
+                table,
+                column,
+            } => write!(
+                f,
+                "{} is missing the column `{column}` in table `{table}`.",
+                path.display()
+            ),
+            Error::InvalidOptions(options) => match (options.year, options.round) {
+                (Some(year), Some(round)) if !options.all_rounds => {
+                    write!(f, "No dataset was found for {year}, round {round}.")
+                }
+                (Some(year), _) => write!(f, "No datasets were found for {year}."),
+                _ => write!(f, "Select a year and a round first."),
+            },
+            Error::TooManyValues => write!(f, "A column has too many distinct values."),
+            Error::NotLoaded => write!(f, "No dataset is loaded."),
+            Error::Cancelled => write!(f, "Loading was cancelled."),
+            Error::Import { path, reason } => {
+                write!(f, "Couldn't import {}: {reason}", path.display())
+            }
+            Error::BranchFamilies { path, reason } => {
+                write!(
+                    f,
+                    "Couldn't read branch families from {}: {reason}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            Error::Sqlite(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        Error::Sqlite(error)
+    }
+}
+
+impl From<rusqlite::types::FromSqlError> for Error {
+    fn from(error: rusqlite::types::FromSqlError) -> Self {
+        Error::Sqlite(error.into())
+    }
+}
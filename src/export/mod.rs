@@ -0,0 +1,425 @@
+mod xlsx;
+
+use crate::{Dataset, Dictionaries, Entry, Error, Result, types::Rank};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// The formats entries can be exported to.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    Markdown,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn as_vec() -> Vec<Self> {
+        vec![
+            ExportFormat::Csv,
+            ExportFormat::Json,
+            ExportFormat::Markdown,
+            ExportFormat::Xlsx,
+        ]
+    }
+
+    /// The file extension for this format, without the leading dot.
+    pub fn get_extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportFormat::Csv => write!(f, "CSV"),
+            ExportFormat::Json => write!(f, "JSON"),
+            ExportFormat::Markdown => write!(f, "Markdown"),
+            ExportFormat::Xlsx => write!(f, "Excel workbook"),
+        }
+    }
+}
+
+/// Names of the text columns, as they appear in [`Entry`].
+const TEXT_FIELDS: [&str; 6] = [
+    "institute",
+    "institute_kind",
+    "branch",
+    "quota",
+    "seat_type",
+    "gender",
+];
+
+/// Headers of the text columns in a Markdown table or workbook.
+const TEXT_HEADERS: [&str; 6] = [
+    "Institute",
+    "Institute type",
+    "Branch",
+    "Quota",
+    "Seat type",
+    "Gender",
+];
+
+fn get_text<'a>(entry: &Entry, dictionaries: &'a Dictionaries) -> [&'a str; 6] {
+    [
+        dictionaries.institute.get(entry.institute),
+        dictionaries.institute_kind.get(entry.institute_kind),
+        dictionaries.branch.get(entry.branch),
+        dictionaries.quota.get(entry.quota),
+        dictionaries.seat_type.get(entry.seat_type),
+        dictionaries.gender.get(entry.gender),
+    ]
+}
+
+/// Write `entries` of `dataset` to `writer` in `format`, in the order they are given.
+///
+/// When there are several rounds, text formats write the ranks of each alongside
+/// the ranks of the last one, and workbooks get a sheet for each.
+pub fn export<'a>(
+    format: ExportFormat,
+    writer: &mut impl Write,
+    dataset: &Dataset,
+    entries: impl Iterator<Item = &'a Entry>,
+) -> std::io::Result<()> {
+    let dictionaries = dataset.get_dictionaries();
+    let rounds = dataset.get_rounds();
+
+    match format {
+        ExportFormat::Csv => write_csv(writer, dictionaries, rounds, entries),
+        ExportFormat::Json => write_json(writer, dictionaries, rounds, entries),
+        ExportFormat::Markdown => write_markdown(writer, dictionaries, rounds, entries),
+        ExportFormat::Xlsx => xlsx::write_xlsx(writer, dataset, entries),
+    }
+}
+
+/// Get the ranks of every round as cells, or an empty cell for missing rounds.
+fn get_round_cells(entry: &Entry, rounds: &[u8]) -> Vec<String> {
+    if rounds.len() < 2 {
+        return Vec::new();
+    }
+
+    entry
+        .rounds
+        .iter()
+        .flat_map(|ranks| match ranks {
+            Some(ranks) => [ranks.or.to_string(), ranks.cr.to_string()],
+            None => [String::new(), String::new()],
+        })
+        .collect()
+}
+
+fn write_csv<'a>(
+    writer: &mut impl Write,
+    dictionaries: &Dictionaries,
+    rounds: &[u8],
+    entries: impl Iterator<Item = &'a Entry>,
+) -> std::io::Result<()> {
+    let mut header: Vec<String> = TEXT_FIELDS.map(String::from).into();
+
+    header.extend(["domain".into(), "or".into(), "cr".into()]);
+
+    if rounds.len() > 1 {
+        for round in rounds {
+            header.push(format!("or_r{round}"));
+            header.push(format!("cr_r{round}"));
+        }
+    }
+
+    writeln!(writer, "{}", header.join(","))?;
+
+    for entry in entries {
+        let mut row: Vec<String> = get_text(entry, dictionaries).map(escape_csv).into();
+
+        row.extend([
+            entry.domain.to_string(),
+            entry.or.to_string(),
+            entry.cr.to_string(),
+        ]);
+        row.extend(get_round_cells(entry, rounds));
+
+        writeln!(writer, "{}", row.join(","))?;
+    }
+
+    Ok(())
+}
+
+fn write_json<'a>(
+    writer: &mut impl Write,
+    dictionaries: &Dictionaries,
+    rounds: &[u8],
+    entries: impl Iterator<Item = &'a Entry>,
+) -> std::io::Result<()> {
+    write!(writer, "[")?;
+
+    for (i, entry) in entries.enumerate() {
+        if i > 0 {
+            write!(writer, ",")?;
+        }
+
+        write!(writer, "\n  {{")?;
+
+        for (field, value) in TEXT_FIELDS.iter().zip(get_text(entry, dictionaries)) {
+            write!(writer, "\"{field}\": {}, ", escape_json(value))?;
+        }
+
+        write!(
+            writer,
+            "\"domain\": {}, \"or\": {}, \"cr\": {}, \"rounds\": [",
+            escape_json(&entry.domain.to_string()),
+            get_json_rank(entry.or),
+            get_json_rank(entry.cr)
+        )?;
+
+        for (j, (round, ranks)) in rounds.iter().zip(&entry.rounds).enumerate() {
+            if j > 0 {
+                write!(writer, ", ")?;
+            }
+
+            match ranks {
+                Some(ranks) => write!(
+                    writer,
+                    "{{\"round\": {round}, \"or\": {}, \"cr\": {}}}",
+                    get_json_rank(ranks.or),
+                    get_json_rank(ranks.cr)
+                )?,
+                None => write!(writer, "null")?,
+            }
+        }
+
+        write!(writer, "]}}")?;
+    }
+
+    writeln!(writer, "\n]")
+}
+
+/// Write a regular rank as a number, or a preparatory rank as a string like `"123P"`
+/// so that it can't be mistaken for a regular rank.
+fn get_json_rank(rank: Rank) -> String {
+    match rank {
+        Rank::Regular(rank) => rank.to_string(),
+        Rank::Preparatory(_) => format!("\"{rank}\""),
+    }
+}
+
+fn write_markdown<'a>(
+    writer: &mut impl Write,
+    dictionaries: &Dictionaries,
+    rounds: &[u8],
+    entries: impl Iterator<Item = &'a Entry>,
+) -> std::io::Result<()> {
+    let mut header: Vec<String> = TEXT_HEADERS.map(String::from).into();
+
+    header.extend(["Exam".into(), "Opening rank".into(), "Closing rank".into()]);
+
+    if rounds.len() > 1 {
+        for round in rounds {
+            header.push(format!("OR (R{round})"));
+            header.push(format!("CR (R{round})"));
+        }
+    }
+
+    // Ranks are aligned to the right.
+    let alignments: Vec<&str> = header
+        .iter()
+        .enumerate()
+        .map(|(i, _)| if i > TEXT_HEADERS.len() { "--:" } else { "---" })
+        .collect();
+
+    writeln!(writer, "| {} |", header.join(" | "))?;
+    writeln!(writer, "| {} |", alignments.join(" | "))?;
+
+    for entry in entries {
+        let mut row: Vec<String> = get_text(entry, dictionaries).map(escape_markdown).into();
+
+        row.extend([
+            entry.domain.to_string(),
+            entry.or.to_string(),
+            entry.cr.to_string(),
+        ]);
+        row.extend(
+            get_round_cells(entry, rounds)
+                .into_iter()
+                .map(|cell| if cell.is_empty() { "—".into() } else { cell }),
+        );
+
+        writeln!(writer, "| {} |", row.join(" | "))?;
+    }
+
+    Ok(())
+}
+
+/// Quote `value` if it contains characters with a meaning in CSV.
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.into()
+    }
+}
+
+/// Quote `value` as a JSON string.
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+
+    escaped.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+/// Escape `value` for a Markdown table cell, which can't contain pipes or line breaks.
+fn escape_markdown(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+impl Dataset {
+    /// Write the entries matching the filters to `writer` in `format`,
+    /// in their sorted order.
+    pub fn export(&self, format: ExportFormat, writer: &mut impl Write) -> std::io::Result<()> {
+        export(format, writer, self, self.get_entries())
+    }
+
+    /// Write the entries matching the filters to the file at `path`, replacing it.
+    pub fn export_to_file(&self, format: ExportFormat, path: &Path) -> Result<()> {
+        let io_error = |error| Error::Io {
+            path: path.into(),
+            error,
+        };
+
+        let mut writer = BufWriter::new(File::create(path).map_err(io_error)?);
+
+        self.export(format, &mut writer).map_err(io_error)?;
+        writer.flush().map_err(io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Catalog, DEFAULT_ROOT, Options, Sort};
+
+    fn load(options: &Options) -> Dataset {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+
+        let mut dataset = Dataset::default();
+        dataset.load(options, &catalog.get_paths(options)).unwrap();
+        dataset.sort(&Sort::default());
+        dataset
+    }
+
+    fn export_string(dataset: &Dataset, format: ExportFormat) -> String {
+        let mut output = Vec::new();
+
+        dataset.export(format, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape_csv("AI"), "AI");
+        assert_eq!(escape_csv("Patna, Bihar"), "\"Patna, Bihar\"");
+        assert_eq!(escape_csv("a \"b\""), "\"a \"\"b\"\"\"");
+
+        assert_eq!(escape_json("a \"b\"\n"), "\"a \\\"b\\\"\\n\"");
+        assert_eq!(escape_json("\u{1}"), "\"\\u0001\"");
+
+        assert_eq!(escape_markdown("a | b  \n"), "a \\| b");
+    }
+
+    #[test]
+    fn export_follows_filters_and_sort() {
+        let mut dataset = load(&Options::new(2024, 5));
+        let (filters, dictionaries) = dataset.get_filters_with_dictionaries();
+
+        filters.quota.set_all(false);
+        filters
+            .quota
+            .set(dictionaries.quota.get_id("AI").unwrap() as usize, true);
+        dataset.apply_filters();
+
+        let count = dataset.get_entry_count();
+
+        // CSV
+        let csv = export_string(&dataset, ExportFormat::Csv);
+        let mut lines = csv.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some("institute,institute_kind,branch,quota,seat_type,gender,domain,or,cr")
+        );
+
+        let first = dataset.get_entries().next().unwrap();
+
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .ends_with(&format!(",{},{}", first.or, first.cr))
+        );
+
+        // Markdown has a header and separator row
+        let markdown = export_string(&dataset, ExportFormat::Markdown);
+
+        assert_eq!(markdown.lines().count(), count + 2);
+        assert!(markdown.lines().skip(2).all(|line| line.contains("| AI |")));
+
+        // JSON has an object per entry
+        let json = export_string(&dataset, ExportFormat::Json);
+
+        assert!(json.starts_with("[\n  {\"institute\": "));
+        assert_eq!(json.matches("\"quota\": \"AI\"").count(), count);
+
+        // Workbooks are zip archives
+        let mut xlsx = Vec::new();
+
+        dataset.export(ExportFormat::Xlsx, &mut xlsx).unwrap();
+        assert!(xlsx.starts_with(b"PK"));
+    }
+
+    #[test]
+    fn export_every_round() {
+        let dataset = load(&Options {
+            year: Some(2024),
+            round: None,
+            all_rounds: true,
+        });
+
+        let csv = export_string(&dataset, ExportFormat::Csv);
+
+        assert!(csv.lines().next().unwrap().ends_with(",or_r5,cr_r5"));
+
+        let json = export_string(&dataset, ExportFormat::Json);
+
+        assert_eq!(
+            json.matches("\"institute\": ").count(),
+            dataset.get_entry_count()
+        );
+        assert!(json.contains("{\"round\": 5, \"or\": "));
+    }
+}
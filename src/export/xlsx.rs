@@ -0,0 +1,235 @@
+use super::{TEXT_HEADERS, get_text};
+use crate::{
+    Dataset, Dictionary, Entry,
+    types::{BitSet, Rank, RankDomain},
+};
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use std::io::Write;
+
+/// The most characters a cell can hold.
+const MAX_CELL_LENGTH: usize = 32_767;
+
+/// Write `entries` as a workbook with a sheet for every round of `dataset`,
+/// followed by a sheet recording how the entries were selected.
+pub(super) fn write_xlsx<'a>(
+    writer: &mut impl Write,
+    dataset: &Dataset,
+    entries: impl Iterator<Item = &'a Entry>,
+) -> std::io::Result<()> {
+    let buffer = build_workbook(dataset, entries)
+        .and_then(|mut workbook| workbook.save_to_buffer())
+        .map_err(std::io::Error::other)?;
+
+    writer.write_all(&buffer)
+}
+
+fn build_workbook<'a>(
+    dataset: &Dataset,
+    entries: impl Iterator<Item = &'a Entry>,
+) -> Result<Workbook, XlsxError> {
+    let entries: Vec<&Entry> = entries.collect();
+    let dictionaries = dataset.get_dictionaries();
+    let bold = Format::new().set_bold();
+
+    let mut workbook = Workbook::new();
+
+    for (i, round) in dataset.get_rounds().iter().enumerate() {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(format!("Round {round}"))?;
+
+        write_header(
+            sheet,
+            TEXT_HEADERS
+                .iter()
+                .chain(&["Exam", "Opening rank", "Closing rank"]),
+            &bold,
+        )?;
+
+        // Entries which aren't offered in this round are left out of its sheet.
+        let rows = entries
+            .iter()
+            .filter_map(|entry| Some((entry, entry.rounds.get(i).copied()??)));
+
+        for (row, (entry, ranks)) in (1..).zip(rows) {
+            let mut column = 0;
+
+            for text in get_text(entry, dictionaries) {
+                sheet.write_string(row, column, text.trim())?;
+                column += 1;
+            }
+
+            sheet.write_string(row, column, entry.domain.to_string())?;
+            write_rank(sheet, row, column + 1, ranks.or)?;
+            write_rank(sheet, row, column + 2, ranks.cr)?;
+        }
+
+        sheet.set_freeze_panes(1, 0)?;
+        sheet.autofit();
+    }
+
+    write_metadata(workbook.add_worksheet(), dataset, entries.len(), &bold)?;
+
+    Ok(workbook)
+}
+
+/// Write a regular rank as a number, or a preparatory rank as text like `123P`
+/// so that it isn't sorted among the regular ranks.
+fn write_rank(sheet: &mut Worksheet, row: u32, column: u16, rank: Rank) -> Result<(), XlsxError> {
+    match rank {
+        Rank::Regular(rank) => sheet.write_number(row, column, rank)?,
+        Rank::Preparatory(_) => sheet.write_string(row, column, rank.to_string())?,
+    };
+
+    Ok(())
+}
+
+fn write_header<'a>(
+    sheet: &mut Worksheet,
+    headers: impl Iterator<Item = &'a &'a str>,
+    format: &Format,
+) -> Result<(), XlsxError> {
+    for (column, header) in (0..).zip(headers) {
+        sheet.write_string_with_format(0, column, *header, format)?;
+    }
+
+    Ok(())
+}
+
+/// Record the options and filters the exported entries were selected with.
+fn write_metadata(
+    sheet: &mut Worksheet,
+    dataset: &Dataset,
+    count: usize,
+    bold: &Format,
+) -> Result<(), XlsxError> {
+    sheet.set_name("Metadata")?;
+    write_header(sheet, ["Field", "Value"].iter(), bold)?;
+
+    let options = dataset.get_options();
+    let filters = dataset.get_filters();
+    let dictionaries = dataset.get_dictionaries();
+
+    let rounds: Vec<String> = dataset
+        .get_rounds()
+        .iter()
+        .map(|round| round.to_string())
+        .collect();
+
+    let mut fields = vec![
+        (
+            "Year".to_string(),
+            options
+                .year
+                .map(|year| year.to_string())
+                .unwrap_or_default(),
+        ),
+        ("Rounds".into(), rounds.join(", ")),
+        ("Rows".into(), count.to_string()),
+    ];
+
+    for (name, selected, dictionary) in [
+        (
+            "Institute type",
+            &filters.institute_kind,
+            &dictionaries.institute_kind,
+        ),
+        ("Institute", &filters.institute, &dictionaries.institute),
+        ("Branch", &filters.branch, &dictionaries.branch),
+        ("Quota", &filters.quota, &dictionaries.quota),
+        ("Seat type", &filters.seat_type, &dictionaries.seat_type),
+        ("Gender", &filters.gender, &dictionaries.gender),
+        ("Duration", &filters.duration, &dictionaries.duration),
+        ("Degree", &filters.degree, &dictionaries.degree),
+    ] {
+        fields.push((name.into(), describe_selection(selected, dictionary)));
+    }
+
+    if !filters.get_search().is_empty() {
+        fields.push(("Search".into(), filters.get_search().get_query().into()));
+    }
+
+    for (domain, ranks) in [
+        (RankDomain::Advanced, &filters.advanced),
+        (RankDomain::Main, &filters.main),
+    ] {
+        if ranks.is_empty() {
+            continue;
+        }
+
+        fields.push((
+            format!("Opening rank ({domain})"),
+            format!("{} to {}", ranks.or.start, ranks.or.end),
+        ));
+        fields.push((
+            format!("Closing rank ({domain})"),
+            format!("{} to {}", ranks.cr.start, ranks.cr.end),
+        ));
+    }
+
+    for (row, (field, value)) in (1..).zip(fields) {
+        sheet.write_string(row, 0, field)?;
+        sheet.write_string(row, 1, value)?;
+    }
+
+    sheet.autofit();
+
+    Ok(())
+}
+
+/// Describe which values of `dictionary` are selected, listing
+/// whichever of the selected or deselected values is shorter.
+fn describe_selection(selected: &BitSet, dictionary: &Dictionary) -> String {
+    let count = selected.count();
+
+    let list = |checked: bool| {
+        let values: Vec<&str> = (0..dictionary.len())
+            .filter(|&id| selected.contains(id) == checked)
+            .map(|id| dictionary.get(id as u16).trim())
+            .collect();
+
+        values.join("; ")
+    };
+
+    let description = if count == dictionary.len() {
+        "All".into()
+    } else if count == 0 {
+        "None".into()
+    } else if count * 2 > dictionary.len() {
+        format!("All except: {}", list(false))
+    } else {
+        list(true)
+    };
+
+    // Cells can't hold more, so long lists are cut short.
+    match description.char_indices().nth(MAX_CELL_LENGTH - 1) {
+        Some((end, _)) => format!("{}…", &description[..end]),
+        None => description,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_descriptions() {
+        let mut dictionary = Dictionary::default();
+
+        for value in ["AI", "HS", "OS"] {
+            dictionary.intern(value).unwrap();
+        }
+
+        let mut selected = BitSet::new(3, true);
+
+        assert_eq!(describe_selection(&selected, &dictionary), "All");
+
+        selected.set(1, false);
+        assert_eq!(describe_selection(&selected, &dictionary), "All except: HS");
+
+        selected.set(2, false);
+        assert_eq!(describe_selection(&selected, &dictionary), "AI");
+
+        selected.set_all(false);
+        assert_eq!(describe_selection(&selected, &dictionary), "None");
+    }
+}
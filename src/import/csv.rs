@@ -0,0 +1,180 @@
+use super::{HeaderMapping, Import, Rejected};
+use crate::{Error, Result};
+use std::{fs::File, io::Read, path::Path};
+
+/// Parse the rows of a CSV file with a header row, reading each field
+/// from the column `mapping` names for it.
+///
+/// Rows with a missing value or an invalid rank are rejected, along with the line they start
+/// on. Fails if the header doesn't name a column for every field which isn't optional.
+pub fn parse_csv(
+    reader: impl Read,
+    mapping: &HeaderMapping,
+) -> std::result::Result<Import, String> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(reader);
+
+    let columns = mapping.find_columns(reader.headers().map_err(|error| error.to_string())?)?;
+
+    let mut import = Import::default();
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => match (error.kind(), error.position()) {
+                (::csv::ErrorKind::Utf8 { .. }, Some(position)) => {
+                    import.rejected.push(Rejected {
+                        line: position.line() as usize,
+                        reason: "invalid UTF-8".into(),
+                    });
+
+                    continue;
+                }
+                _ => return Err(error.to_string()),
+            },
+        };
+
+        let line = record
+            .position()
+            .map_or(0, |position| position.line() as usize);
+
+        match columns.read(|column| record.get(column)) {
+            Ok(row) => import.rows.push(row),
+            Err(reason) => import.rejected.push(Rejected { line, reason }),
+        }
+    }
+
+    Ok(import)
+}
+
+/// Read and parse a CSV file with [`parse_csv`].
+pub fn read_csv(path: &Path, mapping: &HeaderMapping) -> Result<Import> {
+    let file = File::open(path).map_err(|error| Error::Io {
+        path: path.into(),
+        error,
+    })?;
+
+    parse_csv(file, mapping).map_err(|reason| Error::Import {
+        path: path.into(),
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Catalog, DEFAULT_ROOT, Dataset, ExportFormat, ImportField, Options, normalize_name,
+    };
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/orcr.csv");
+
+    #[test]
+    fn csv_rows() {
+        let mapping = HeaderMapping::default()
+            .with_header(ImportField::Institute, "College")
+            .with_header(ImportField::OpeningRank, "Opening")
+            .with_header(ImportField::ClosingRank, "Closing");
+
+        let import = parse_csv(FIXTURE.as_bytes(), &mapping).unwrap();
+
+        assert_eq!(import.rows.len(), 4);
+
+        // Institute types come from the file, or else the bundled list
+        assert_eq!(
+            import.rows[0].institute_type,
+            "Indian Institute of Technology"
+        );
+        assert_eq!(
+            import.rows[1].institute_type,
+            "Government Funded Technical Institutions"
+        );
+        assert_eq!(
+            import.rows[2].institute_type,
+            "National Institute of Technology"
+        );
+
+        // Quoted values and entities
+        assert_eq!(
+            import.rows[2].branch,
+            "Computer Science and Engineering (4 Years, Bachelor of Technology)"
+        );
+        assert_eq!(
+            import.rows[1].institute,
+            "School of Planning &amp; Architecture, Bhopal"
+        );
+        assert!(import.rows[3].cr.is_preparatory());
+
+        assert_eq!(
+            import.rejected,
+            [
+                Rejected {
+                    line: 6,
+                    reason: "invalid rank \"12.5\"".into()
+                },
+                Rejected {
+                    line: 7,
+                    reason: "missing cr".into()
+                },
+            ]
+        );
+
+        // The default headers don't match
+        assert_eq!(
+            parse_csv(FIXTURE.as_bytes(), &HeaderMapping::default())
+                .err()
+                .unwrap(),
+            "no column was found for institute, or, cr"
+        );
+    }
+
+    #[test]
+    fn csv_imports_exports() {
+        let catalog = Catalog::with_roots([DEFAULT_ROOT.into()]);
+        let options = Options::new(2024, 5);
+
+        let mut dataset = Dataset::default();
+        dataset
+            .load(&options, &catalog.get_paths(&options))
+            .unwrap();
+
+        let mut csv = Vec::new();
+        dataset.export(ExportFormat::Csv, &mut csv).unwrap();
+
+        let import = parse_csv(csv.as_slice(), &HeaderMapping::default()).unwrap();
+
+        assert!(import.rejected.is_empty());
+        assert_eq!(import.rows.len(), dataset.get_entries().len());
+
+        // Round trip into a new dataset
+        let root = std::env::temp_dir().join(format!("orcr-import-csv-{}", std::process::id()));
+        let path = Options::new(2030, 1).get_db_path(&root).unwrap();
+
+        import.write(&path).unwrap();
+
+        let mut imported = Dataset::default();
+        imported
+            .load(&Options::new(2030, 1), &[(1, &path)])
+            .unwrap();
+
+        let get_names = |dataset: &Dataset| {
+            let mut names: Vec<String> = dataset
+                .get_dictionaries()
+                .institute
+                .values()
+                .iter()
+                .map(|name| normalize_name(name))
+                .collect();
+
+            names.sort();
+            names
+        };
+
+        assert_eq!(get_names(&imported), get_names(&dataset));
+        assert_eq!(imported.get_entries().len(), dataset.get_entries().len());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
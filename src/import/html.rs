@@ -0,0 +1,195 @@
+use super::{HeaderMapping, Import, Rejected};
+use crate::{Error, Result};
+use scraper::{ElementRef, Html, Selector};
+use std::{path::Path, sync::LazyLock};
+
+static TABLE: LazyLock<Selector> = LazyLock::new(|| Selector::parse("table").unwrap());
+static ROW: LazyLock<Selector> = LazyLock::new(|| Selector::parse("tr").unwrap());
+
+/// Parse the rows of an OR-CR results page saved from the JoSAA website.
+///
+/// The first table with the columns of the results is read, with its columns in any order.
+/// Rows which aren't results, such as the pager, are skipped, and rows with a missing
+/// value or an invalid rank are rejected.
+///
+/// Returns [`None`] if the page has no such table.
+pub fn parse_html(html: &str) -> Option<Import> {
+    let document = Html::parse_document(html);
+
+    document.select(&TABLE).find_map(parse_table)
+}
+
+/// Read and parse an OR-CR results page with [`parse_html`].
+pub fn read_html(path: &Path) -> Result<Import> {
+    let html = std::fs::read_to_string(path).map_err(|error| Error::Io {
+        path: path.into(),
+        error,
+    })?;
+
+    parse_html(&html).ok_or_else(|| Error::Import {
+        path: path.into(),
+        reason: "no table of opening and closing ranks was found".into(),
+    })
+}
+
+fn parse_table(table: ElementRef) -> Option<Import> {
+    // Nested tables, such as the pager, have rows of their own.
+    let rows: Vec<Vec<String>> = table
+        .select(&ROW)
+        .filter(|row| {
+            row.ancestors()
+                .filter_map(ElementRef::wrap)
+                .find(|element| element.value().name() == "table")
+                .map(|element| element.id())
+                == Some(table.id())
+        })
+        .map(|row| {
+            row.children()
+                .filter_map(ElementRef::wrap)
+                .filter(|cell| matches!(cell.value().name(), "th" | "td"))
+                .map(get_text)
+                .collect()
+        })
+        .collect();
+
+    let mapping = HeaderMapping::default();
+    let is_header = |cells: &[String]| mapping.find_columns(cells.iter().map(String::as_str));
+
+    let (header, columns) = rows
+        .iter()
+        .enumerate()
+        .find_map(|(i, cells)| Some((i, is_header(cells).ok()?)))?;
+
+    let mut import = Import::default();
+
+    for (i, cells) in rows.iter().enumerate().skip(header + 1) {
+        // Rows spanning the table, such as the pager, and repeated headers
+        if cells.len() <= 1 || is_header(cells).is_ok() {
+            continue;
+        }
+
+        match columns.read(|column| cells.get(column).map(String::as_str)) {
+            Ok(row) => import.rows.push(row),
+            Err(reason) => import.rejected.push(Rejected {
+                line: i + 1,
+                reason,
+            }),
+        }
+    }
+
+    Some(import)
+}
+
+fn get_text(cell: ElementRef) -> String {
+    cell.text().collect::<String>().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dataset, ImportedRow, Options, normalize_name, types::Rank};
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/orcr.html");
+
+    #[test]
+    fn html_rows() {
+        let import = parse_html(FIXTURE).unwrap();
+
+        assert_eq!(import.rows.len(), 6);
+        assert_eq!(
+            import.rows[0],
+            ImportedRow {
+                institute: "Indian Institute  of Technology Bhubaneswar".into(),
+                institute_type: "Indian Institute of Technology".into(),
+                branch: "Civil Engineering (4 Years, Bachelor of Technology)".into(),
+                quota: "AI".into(),
+                seat_type: "OPEN".into(),
+                gender: "Gender-Neutral".into(),
+                or: "9106".parse().unwrap(),
+                cr: "14782".parse().unwrap(),
+            }
+        );
+
+        // Entities are kept escaped
+        assert_eq!(
+            import.rows[2].institute,
+            "Indian Institute of Information Technology Design &amp; Manufacturing Kurnool"
+        );
+        assert_eq!(
+            import.rows[2].institute_type,
+            "Indian Institute of Information Technology"
+        );
+
+        // Preparatory ranks
+        assert_eq!(import.rows[4].or, Rank::Regular(412));
+        assert_eq!(import.rows[4].cr, Rank::Preparatory(38));
+        assert_eq!(import.rows[5].or, Rank::Preparatory(127));
+
+        assert_eq!(
+            import.rejected,
+            [
+                Rejected {
+                    line: 8,
+                    reason: "invalid rank \"--\"".into()
+                },
+                Rejected {
+                    line: 9,
+                    reason: "missing quota".into()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn html_without_results() {
+        assert!(
+            parse_html("<html><body><table><tr><td>1</td></tr></table></body></html>").is_none()
+        );
+        assert!(parse_html("").is_none());
+
+        assert!(matches!(
+            read_html(Path::new("tests/fixtures/missing.html")),
+            Err(Error::Io { .. })
+        ));
+        assert!(matches!(
+            read_html(Path::new("Cargo.toml")),
+            Err(Error::Import { .. })
+        ));
+    }
+
+    #[test]
+    fn html_imports_into_dataset() {
+        let root = std::env::temp_dir().join(format!("orcr-import-{}", std::process::id()));
+        let options = Options::new(2030, 1);
+        let path = options.get_db_path(&root).unwrap();
+
+        let summary = parse_html(FIXTURE).unwrap().write(&path).unwrap();
+
+        assert_eq!(summary.written, 6);
+        assert_eq!(summary.preparatory, 2);
+
+        let mut dataset = Dataset::default();
+        dataset.load(&options, &[(1, &path)]).unwrap();
+
+        let dictionaries = dataset.get_dictionaries();
+        let kinds: Vec<String> = dataset
+            .get_entries()
+            .map(|entry| normalize_name(dictionaries.institute_kind.get(entry.institute_kind)))
+            .collect();
+
+        assert_eq!(kinds.len(), 6);
+        assert!(kinds.contains(&"National Institute of Technology".into()));
+
+        // Preparatory ranks are kept
+        assert!(
+            dataset
+                .get_entries()
+                .any(|entry| entry.cr == Rank::Preparatory(38))
+        );
+
+        // Importing again replaces the dataset
+        parse_html(FIXTURE).unwrap().write(&path).unwrap();
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,269 @@
+use super::{ImportedRow, escape};
+use crate::{infer_institute_type, types::Rank};
+use std::{fmt::Display, str::FromStr};
+
+/// The gender recorded for sources from before JoSAA had female-only seats.
+const NO_GENDER: &str = "NA";
+
+/// A field of an [`ImportedRow`], which is read from a column of its source.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImportField {
+    Institute,
+    /// Optional, since it can be inferred from the institute.
+    InstituteType,
+    Branch,
+    Quota,
+    SeatType,
+    /// Optional, since sources from before 2018 don't have it.
+    Gender,
+    OpeningRank,
+    ClosingRank,
+}
+
+impl ImportField {
+    pub const ALL: [Self; 8] = [
+        Self::Institute,
+        Self::InstituteType,
+        Self::Branch,
+        Self::Quota,
+        Self::SeatType,
+        Self::Gender,
+        Self::OpeningRank,
+        Self::ClosingRank,
+    ];
+
+    /// Get the name of the field, such as `seat_type`.
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Self::Institute => "institute",
+            Self::InstituteType => "institute_type",
+            Self::Branch => "branch",
+            Self::Quota => "quota",
+            Self::SeatType => "seat_type",
+            Self::Gender => "gender",
+            Self::OpeningRank => "or",
+            Self::ClosingRank => "cr",
+        }
+    }
+
+    fn is_optional(self) -> bool {
+        matches!(self, Self::InstituteType | Self::Gender)
+    }
+
+    /// Headers the field is read from by default, as normalized by [`normalize_header`].
+    fn get_default_headers(self) -> &'static [&'static str] {
+        match self {
+            Self::Institute => &["institute", "institutename"],
+            Self::InstituteType => &["institutetype", "institutekind"],
+            Self::Branch => &["academicprogramname", "branch", "program"],
+            Self::Quota => &["quota"],
+            Self::SeatType => &["seattype", "category"],
+            Self::Gender => &["gender"],
+            Self::OpeningRank => &["openingrank", "or", "orank"],
+            Self::ClosingRank => &["closingrank", "cr", "crank"],
+        }
+    }
+}
+
+impl FromStr for ImportField {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.get_name() == text)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|field| field.get_name()).collect();
+
+                format!(
+                    "unknown field {text:?}, expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl Display for ImportField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_name())
+    }
+}
+
+/// The headers of the columns each [`ImportField`] is read from.
+///
+/// Headers are matched ignoring case, whitespace and punctuation,
+/// so `Seat Type` matches `seat_type`.
+#[derive(Clone, Debug)]
+pub struct HeaderMapping {
+    headers: [Vec<String>; ImportField::ALL.len()],
+}
+
+impl Default for HeaderMapping {
+    /// Reads the headers of JoSAA result pages, and of CSV exports.
+    fn default() -> Self {
+        Self {
+            headers: ImportField::ALL.map(|field| {
+                field
+                    .get_default_headers()
+                    .iter()
+                    .map(|header| header.to_string())
+                    .collect()
+            }),
+        }
+    }
+}
+
+impl HeaderMapping {
+    /// Read `field` from the column with `header`, instead of any of its default headers.
+    pub fn with_header(mut self, field: ImportField, header: &str) -> Self {
+        self.headers[field as usize] = vec![normalize_header(header)];
+        self
+    }
+
+    /// Find the column of each field in a header row.
+    ///
+    /// Fails with the names of any fields which aren't optional and have no column.
+    pub(super) fn find_columns<'a>(
+        &self,
+        header: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Columns, String> {
+        let header: Vec<String> = header.into_iter().map(normalize_header).collect();
+
+        let columns = self.headers.each_ref().map(|names| {
+            header
+                .iter()
+                .position(|column| names.iter().any(|name| name == column))
+        });
+
+        let missing: Vec<&str> = ImportField::ALL
+            .into_iter()
+            .filter(|field| !field.is_optional() && columns[*field as usize].is_none())
+            .map(ImportField::get_name)
+            .collect();
+
+        if missing.is_empty() {
+            Ok(Columns(columns))
+        } else {
+            Err(format!("no column was found for {}", missing.join(", ")))
+        }
+    }
+}
+
+/// The column of each [`ImportField`], found by [`HeaderMapping::find_columns`].
+pub(super) struct Columns([Option<usize>; ImportField::ALL.len()]);
+
+impl Columns {
+    /// Read a row, given the text of its cells by column.
+    ///
+    /// Fails if a field is empty or a rank is invalid.
+    pub(super) fn read<'a>(
+        &self,
+        cells: impl Fn(usize) -> Option<&'a str>,
+    ) -> Result<ImportedRow, String> {
+        let get = |field: ImportField| {
+            self.0[field as usize]
+                .and_then(&cells)
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+        };
+        let text = |field| {
+            get(field)
+                .map(escape)
+                .ok_or_else(|| format!("missing {field}"))
+        };
+        let rank = |field| text(field)?.parse::<Rank>();
+
+        let institute = text(ImportField::Institute)?;
+
+        Ok(ImportedRow {
+            institute_type: get(ImportField::InstituteType)
+                .map_or_else(|| infer_institute_type(&institute).into(), escape),
+            branch: text(ImportField::Branch)?,
+            quota: text(ImportField::Quota)?,
+            seat_type: text(ImportField::SeatType)?,
+            gender: match self.0[ImportField::Gender as usize] {
+                Some(_) => text(ImportField::Gender)?,
+                None => NO_GENDER.into(),
+            },
+            or: rank(ImportField::OpeningRank)?,
+            cr: rank(ImportField::ClosingRank)?,
+            institute,
+        })
+    }
+}
+
+/// Normalize a header for matching, by keeping only its letters and digits in lowercase.
+fn normalize_header(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_mappings() {
+        let mapping = HeaderMapping::default();
+
+        // Result pages
+        let columns = mapping
+            .find_columns([
+                "Institute",
+                "Academic Program Name",
+                "Quota",
+                "Seat Type",
+                "Gender",
+                "Opening Rank",
+                "Closing Rank",
+            ])
+            .unwrap();
+
+        assert_eq!(columns.0[ImportField::SeatType as usize], Some(3));
+        assert_eq!(columns.0[ImportField::InstituteType as usize], None);
+
+        // CSV exports
+        assert!(
+            mapping
+                .find_columns(
+                    "institute,institute_kind,branch,quota,seat_type,gender,domain,or,cr"
+                        .split(',')
+                )
+                .is_ok()
+        );
+
+        // Missing columns
+        assert_eq!(
+            mapping
+                .find_columns(["Institute", "Program", "Quota", "Category", "Gender"])
+                .err()
+                .unwrap(),
+            "no column was found for or, cr"
+        );
+
+        // Custom headers replace the defaults
+        let mapping = mapping
+            .with_header(ImportField::OpeningRank, "OR (R1)")
+            .with_header(ImportField::ClosingRank, "Closing");
+        let columns = mapping
+            .find_columns([
+                "Institute",
+                "Program",
+                "Quota",
+                "Category",
+                "or",
+                "OR (R1)",
+                "Closing",
+            ])
+            .unwrap();
+
+        assert_eq!(columns.0[ImportField::OpeningRank as usize], Some(5));
+        assert_eq!(columns.0[ImportField::ClosingRank as usize], Some(6));
+
+        assert_eq!("seat_type".parse(), Ok(ImportField::SeatType));
+        assert!("seat type".parse::<ImportField>().is_err());
+    }
+}
@@ -0,0 +1,135 @@
+mod csv;
+mod html;
+mod mapping;
+
+pub use csv::*;
+pub use html::*;
+pub use mapping::*;
+
+use crate::{Error, Result, types::Rank};
+use rusqlite::Connection;
+use rustc_hash::FxHashSet;
+use std::path::Path;
+
+/// A row of the `data` table, along with the `instituteType` of its institute.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ImportedRow {
+    pub institute: String,
+    pub institute_type: String,
+    pub branch: String,
+    pub quota: String,
+    pub seat_type: String,
+    pub gender: String,
+    pub or: Rank,
+    pub cr: Rank,
+}
+
+/// A row which couldn't be imported.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Rejected {
+    /// The 1-based line the row starts on in its source. For HTML pages,
+    /// this is the number of the row in its table, counting the header.
+    pub line: usize,
+    pub reason: String,
+}
+
+/// The rows read from one or more sources.
+#[derive(Default, Debug)]
+pub struct Import {
+    pub rows: Vec<ImportedRow>,
+    pub rejected: Vec<Rejected>,
+}
+
+/// How many rows were written by [`Import::write`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ImportSummary {
+    pub written: usize,
+
+    /// How many of the written rows have a preparatory rank.
+    pub preparatory: usize,
+}
+
+impl Import {
+    /// Write the rows as a dataset at `path`, with the schema [`crate::Dataset::load`] reads.
+    /// An existing dataset at `path` is only replaced once the new one is complete.
+    ///
+    /// Whether ranks are preparatory ranks is stored in the
+    /// [`PREPARATORY_COLUMNS`](crate::PREPARATORY_COLUMNS).
+    pub fn write(&self, path: &Path) -> Result<ImportSummary> {
+        let io_error = |error| Error::Io {
+            path: path.into(),
+            error,
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_error)?;
+        }
+
+        let partial = path.with_extension("db.partial");
+
+        // Left behind by an import which didn't finish.
+        if partial.exists() {
+            std::fs::remove_file(&partial).map_err(io_error)?;
+        }
+
+        let mut conn = Connection::open(&partial)?;
+        let tx = conn.transaction()?;
+
+        tx.execute_batch(
+            "CREATE TABLE institutes (institute TEXT, instituteType TEXT);
+             CREATE TABLE data (institute TEXT, branch TEXT, quota TEXT, seatType TEXT, gender TEXT, \
+                orank INT, crank INT, orankPreparatory INT, crankPreparatory INT);",
+        )?;
+
+        let mut summary = ImportSummary {
+            written: 0,
+            preparatory: 0,
+        };
+
+        // Statements borrow the transaction, so they are dropped before it is committed.
+        {
+            let mut institutes = tx.prepare("INSERT INTO institutes VALUES (?1, ?2);")?;
+            let mut data =
+                tx.prepare("INSERT INTO data VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9);")?;
+            let mut seen = FxHashSet::default();
+
+            for row in &self.rows {
+                if row.or.is_preparatory() || row.cr.is_preparatory() {
+                    summary.preparatory += 1;
+                }
+
+                if seen.insert(&row.institute) {
+                    institutes.execute((&row.institute, &row.institute_type))?;
+                }
+
+                data.execute((
+                    &row.institute,
+                    &row.branch,
+                    &row.quota,
+                    &row.seat_type,
+                    &row.gender,
+                    row.or.get_value(),
+                    row.cr.get_value(),
+                    row.or.is_preparatory(),
+                    row.cr.is_preparatory(),
+                ))?;
+
+                summary.written += 1;
+            }
+        }
+
+        tx.commit()?;
+        drop(conn);
+
+        std::fs::rename(&partial, path).map_err(io_error)?;
+
+        Ok(summary)
+    }
+}
+
+/// Escape text the same way as the bundled datasets, which keep `&` as `&amp;`.
+///
+/// Text which is already escaped is kept as it is.
+fn escape(text: &str) -> String {
+    text.replace("&amp;", "&").replace('&', "&amp;")
+}
@@ -1,8 +1,14 @@
 mod app_state;
 mod dataset;
+mod error;
+mod export;
+mod import;
 
 pub use app_state::*;
 pub use dataset::*;
+pub use error::*;
+pub use export::*;
+pub use import::*;
 
 pub mod types;
 pub mod widgets;
@@ -0,0 +1,130 @@
+/// A fixed-size set of bits, used to store which values
+/// of a column are selected.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    /// Create a new bit set of `len` bits, all set to `value`.
+    pub fn new(len: usize, value: bool) -> Self {
+        let mut set = Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        };
+
+        set.set_all(value);
+        set
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the bit at `index`, which is `false` if it is out of bounds.
+    pub fn contains(&self, index: usize) -> bool {
+        index < self.len && self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Set the bit at `index`. Does nothing if it is out of bounds.
+    pub fn set(&mut self, index: usize, value: bool) {
+        if index >= self.len {
+            return;
+        }
+
+        if value {
+            self.words[index / 64] |= 1 << (index % 64);
+        } else {
+            self.words[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    /// Set every bit to `value`.
+    pub fn set_all(&mut self, value: bool) {
+        self.words.fill(if value { u64::MAX } else { 0 });
+
+        // Keep the bits past `len` cleared.
+        if let Some(last) = self.words.last_mut()
+            && !self.len.is_multiple_of(64)
+        {
+            *last &= (1 << (self.len % 64)) - 1;
+        }
+    }
+
+    /// Get the number of set bits.
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_set_new() {
+        // All set
+        let set = BitSet::new(70, true);
+
+        assert_eq!(set.len(), 70);
+        assert_eq!(set.count(), 70);
+        assert!(set.contains(0));
+        assert!(set.contains(69));
+        assert!(!set.contains(70));
+
+        // All clear
+        let set = BitSet::new(70, false);
+
+        assert_eq!(set.count(), 0);
+        assert!(!set.contains(0));
+
+        // Empty
+        assert!(BitSet::default().is_empty());
+        assert_eq!(BitSet::new(0, true).count(), 0);
+    }
+
+    #[test]
+    fn bit_set_set() {
+        let mut set = BitSet::new(130, false);
+
+        set.set(3, true);
+        set.set(64, true);
+        set.set(129, true);
+
+        assert!(set.contains(3));
+        assert!(set.contains(64));
+        assert!(set.contains(129));
+        assert!(!set.contains(4));
+        assert_eq!(set.count(), 3);
+
+        set.set(64, false);
+
+        assert!(!set.contains(64));
+        assert_eq!(set.count(), 2);
+
+        // Out of bounds
+        set.set(130, true);
+
+        assert!(!set.contains(130));
+        assert_eq!(set.count(), 2);
+    }
+
+    #[test]
+    fn bit_set_set_all() {
+        let mut set = BitSet::new(65, false);
+
+        set.set_all(true);
+        assert_eq!(set.count(), 65);
+
+        set.set_all(false);
+        assert_eq!(set.count(), 0);
+    }
+}
@@ -1,3 +1,9 @@
+mod bit_set;
+mod rank;
+mod rank_domain;
 mod rank_range;
 
+pub use bit_set::*;
+pub use rank::*;
+pub use rank_domain::*;
 pub use rank_range::*;
@@ -0,0 +1,118 @@
+use std::{fmt::Display, str::FromStr};
+
+/// An opening or closing rank as JoSAA publishes it.
+///
+/// Ranks are ordered with every preparatory rank after every regular rank,
+/// since preparatory candidates are only offered seats left over by everyone else.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Rank {
+    /// A rank in the common or category rank list.
+    Regular(u32),
+    /// A rank in the preparatory course list for PwD candidates, written like `123P`.
+    Preparatory(u32),
+}
+
+impl Rank {
+    /// Create a rank, which is a preparatory rank if `preparatory` is set.
+    pub fn new(value: u32, preparatory: bool) -> Self {
+        if preparatory {
+            Self::Preparatory(value)
+        } else {
+            Self::Regular(value)
+        }
+    }
+
+    /// Get the number of the rank in its list.
+    pub fn get_value(self) -> u32 {
+        match self {
+            Self::Regular(value) | Self::Preparatory(value) => value,
+        }
+    }
+
+    /// Get the number of the rank if it is a regular rank.
+    pub fn get_regular(self) -> Option<u32> {
+        match self {
+            Self::Regular(value) => Some(value),
+            Self::Preparatory(_) => None,
+        }
+    }
+
+    pub fn is_preparatory(self) -> bool {
+        matches!(self, Self::Preparatory(_))
+    }
+}
+
+impl Display for Rank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Regular(value) => write!(f, "{value}"),
+            Self::Preparatory(value) => write!(f, "{value}P"),
+        }
+    }
+}
+
+impl FromStr for Rank {
+    type Err = String;
+
+    /// Parse a rank such as `1234`, or a preparatory rank such as `56P`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let (value, preparatory) = match text.strip_suffix(['P', 'p']) {
+            Some(value) => (value, true),
+            None => (text, false),
+        };
+
+        match value.parse() {
+            Ok(value) if value > 0 => Ok(Self::new(value, preparatory)),
+            _ => Err(format!("invalid rank {text:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_parsing() {
+        assert_eq!(" 1234 ".parse(), Ok(Rank::Regular(1234)));
+        assert_eq!("56P".parse(), Ok(Rank::Preparatory(56)));
+        assert_eq!("56p".parse(), Ok(Rank::Preparatory(56)));
+        assert_eq!(Rank::Preparatory(56).to_string(), "56P");
+        assert_eq!(Rank::Regular(56).to_string(), "56");
+
+        // Invalid
+        assert!("".parse::<Rank>().is_err());
+        assert!("P".parse::<Rank>().is_err());
+        assert!("0".parse::<Rank>().is_err());
+        assert!("12.5".parse::<Rank>().is_err());
+        assert!("-".parse::<Rank>().is_err());
+    }
+
+    #[test]
+    fn rank_ordering() {
+        assert!(Rank::Regular(1) < Rank::Regular(2));
+        assert!(Rank::Preparatory(1) < Rank::Preparatory(2));
+
+        // Preparatory ranks come after every regular rank
+        assert!(Rank::Regular(u32::MAX) < Rank::Preparatory(1));
+
+        let mut ranks = [
+            Rank::Preparatory(3),
+            Rank::Regular(900),
+            Rank::Preparatory(1),
+            Rank::Regular(4),
+        ];
+        ranks.sort();
+
+        assert_eq!(
+            ranks,
+            [
+                Rank::Regular(4),
+                Rank::Regular(900),
+                Rank::Preparatory(1),
+                Rank::Preparatory(3)
+            ]
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt::Display;
+
+/// The exam whose ranks are used for admission to an institute.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum RankDomain {
+    /// JEE Advanced, used by the IITs.
+    Advanced,
+    /// JEE Main, used by every other institute.
+    Main,
+}
+
+impl RankDomain {
+    /// The `instituteType` of institutes admitting through JEE Advanced.
+    pub const ADVANCED_INSTITUTE_TYPE: &str = "Indian Institute of Technology";
+
+    /// Derive the rank domain from an institute's `instituteType`.
+    pub fn from_institute_type(kind: &str) -> Self {
+        if kind == Self::ADVANCED_INSTITUTE_TYPE {
+            Self::Advanced
+        } else {
+            Self::Main
+        }
+    }
+}
+
+impl Display for RankDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RankDomain::Advanced => write!(f, "JEE Advanced"),
+            RankDomain::Main => write!(f, "JEE Main"),
+        }
+    }
+}
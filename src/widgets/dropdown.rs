@@ -1,4 +1,5 @@
-use egui::{Response, WidgetText};
+use egui::{Id, Key, Modifiers, Response, WidgetText};
+use std::hash::Hash;
 
 /// A possible choice for the dropdown.
 ///
@@ -36,15 +37,54 @@ impl<T> Choice for T where T: PartialEq + Clone {}
 /// };
 /// # });
 /// ```
+///
+/// Long lists can be searched by typing, and navigated with the arrow keys and
+/// enter. Options can be grouped under headers, and disabled one by one:
+/// ```
+/// # use josaa_orcr::widgets::Dropdown;
+/// #
+/// # egui::__run_test_ui(|ui| {
+/// let mut state = "Goa";
+///
+/// Dropdown::with_state(&mut state)
+///     .with_id_salt("state")
+///     .with_options(["Assam", "Goa", "Delhi", "Puducherry"].into_iter())
+///     .with_search()
+///     .with_groups(|state| match *state {
+///         "Delhi" | "Puducherry" => "Union territories",
+///         _ => "States",
+///     })
+///     .with_disabled(|state| *state == "Assam")
+///     .show(ui, |state| *state);
+/// # });
+/// ```
 pub struct Dropdown<'a, T, I>
 where
     T: Choice,
     I: Iterator<Item = T>,
 {
     label: String,
+    id_salt: Option<Id>,
     enabled: bool,
+    search: bool,
     state: &'a mut T,
     options: Option<I>,
+    disabled: Option<IsDisabled<'a, T>>,
+    group: Option<GetGroup<'a, T>>,
+}
+
+/// Whether an option can't be selected.
+type IsDisabled<'a, T> = Box<dyn Fn(&T) -> bool + 'a>;
+/// The name of the group of an option.
+type GetGroup<'a, T> = Box<dyn Fn(&T) -> &'a str + 'a>;
+
+/// What is kept in memory for a searchable dropdown while it is open.
+#[derive(Clone, Default)]
+struct SearchState {
+    query: String,
+    /// The position among the options matching the query
+    /// which enter selects, moved by the arrow keys.
+    highlighted: Option<usize>,
 }
 
 impl<'a, T, I> Dropdown<'a, T, I>
@@ -58,9 +98,13 @@ where
     pub fn with_state(state: &'a mut T) -> Self {
         Self {
             label: "".into(),
+            id_salt: None,
             enabled: true,
+            search: false,
             state,
             options: None,
+            disabled: None,
+            group: None,
         }
     }
 
@@ -77,13 +121,44 @@ where
         self
     }
 
+    /// Set the salt of the dropdown's id, which must be unique among its siblings.
+    ///
+    /// By default the label is used, and dropdowns without a label get an id from
+    /// their position, which changes if widgets are added before them.
+    pub fn with_id_salt(mut self, id_salt: impl Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
     /// Provide an iterator of items `T`, which are all valid values
-    /// the state can take.
+    /// the state can take. The dropdown is empty if this isn't called.
     pub fn with_options(mut self, options: I) -> Self {
         self.options = Some(options);
         self
     }
 
+    /// Show a text box above the options, which hides those not containing its text.
+    ///
+    /// While it is open, the up and down arrow keys move between the options
+    /// shown, and enter selects one.
+    pub fn with_search(mut self) -> Self {
+        self.search = true;
+        self
+    }
+
+    /// Show options for which `disabled` is true, but don't allow selecting them.
+    pub fn with_disabled(mut self, disabled: impl Fn(&T) -> bool + 'a) -> Self {
+        self.disabled = Some(Box::new(disabled));
+        self
+    }
+
+    /// Show a header with the name returned by `group` before options in a different
+    /// group than the one before them. Options should be given in order of their group.
+    pub fn with_groups(mut self, group: impl Fn(&T) -> &'a str + 'a) -> Self {
+        self.group = Some(Box::new(group));
+        self
+    }
+
     /// Render the widget.
     ///
     /// `display` is a function mapping `T` into text to be displayed.
@@ -102,27 +177,332 @@ where
                 ui.label(&self.label);
             }
 
-            ui.add_enabled_ui(self.enabled, |ui| {
-                egui::ComboBox::from_id_salt(&self.label)
-                    // Convert selected value into string or display fallback.
-                    .selected_text(display(self.state))
-                    // Show options in dropdown
-                    .show_ui(ui, |ui| {
-                        // Start recording the response for each selectable value.
-                        let mut response = ui.response();
-                        let options = self.options.expect("No options provided for dropdown.");
-
-                        for option in options {
-                            response |=
-                                ui.selectable_value(self.state, option.clone(), display(&option));
-                        }
-
-                        response
-                    })
-                    .inner
-            })
-            .inner
+            let id_salt = match self.id_salt {
+                Some(id_salt) => id_salt,
+                None if self.label.is_empty() => ui.next_auto_id(),
+                None => Id::new(&self.label),
+            };
+            let search_id = ui.make_persistent_id(id_salt).with("search");
+
+            let state = self.state;
+            let disabled = self.disabled;
+            let group = self.group;
+            let search = self.search;
+
+            let is_enabled =
+                |option: &T| !disabled.as_ref().is_some_and(|disabled| disabled(option));
+
+            let response = ui
+                .add_enabled_ui(self.enabled, |ui| {
+                    egui::ComboBox::from_id_salt(id_salt)
+                        // Convert selected value into string or display fallback.
+                        .selected_text(display(state))
+                        // Clicking the search box mustn't close a searchable dropdown.
+                        .close_behavior(if search {
+                            egui::PopupCloseBehavior::CloseOnClickOutside
+                        } else {
+                            egui::PopupCloseBehavior::CloseOnClick
+                        })
+                        // Show options in dropdown
+                        .show_ui(ui, |ui| {
+                            // Without options, only the current value is shown.
+                            let options: Vec<(T, WidgetText)> = self
+                                .options
+                                .into_iter()
+                                .flatten()
+                                .map(|option| {
+                                    let text = display(&option).into();
+                                    (option, text)
+                                })
+                                .collect();
+
+                            let mut search_state: Option<SearchState> = None;
+                            let mut changed = false;
+                            let mut moved = false;
+
+                            // Only the options matching the search, if any.
+                            let shown: Vec<usize> = if search {
+                                let stored = ui.data(|data| data.get_temp(search_id));
+                                let opened = stored.is_none();
+                                let search_state = search_state.insert(stored.unwrap_or_default());
+
+                                // Keys pressed as it opens, such as the enter which opened
+                                // it, are left alone.
+                                let (up, down, enter) = if opened {
+                                    (false, false, false)
+                                } else {
+                                    ui.input_mut(|input| {
+                                        (
+                                            input.consume_key(Modifiers::NONE, Key::ArrowUp),
+                                            input.consume_key(Modifiers::NONE, Key::ArrowDown),
+                                            input.consume_key(Modifiers::NONE, Key::Enter),
+                                        )
+                                    })
+                                };
+
+                                let edit = ui.add(
+                                    egui::TextEdit::singleline(&mut search_state.query)
+                                        .hint_text("Search"),
+                                );
+
+                                if opened {
+                                    edit.request_focus();
+                                }
+
+                                let query = search_state.query.to_lowercase();
+                                let shown: Vec<usize> = (0..options.len())
+                                    .filter(|&index| {
+                                        options[index].1.text().to_lowercase().contains(&query)
+                                    })
+                                    .collect();
+                                let selectable: Vec<usize> = (0..shown.len())
+                                    .filter(|&position| is_enabled(&options[shown[position]].0))
+                                    .collect();
+
+                                if edit.changed() || opened {
+                                    search_state.highlighted = selectable.first().copied();
+                                }
+
+                                search_state.highlighted =
+                                    navigate(&selectable, search_state.highlighted, up, down);
+                                moved = up || down;
+
+                                if enter && let Some(position) = search_state.highlighted {
+                                    let option = &options[shown[position]].0;
+
+                                    changed = state != option;
+                                    *state = option.clone();
+                                    ui.memory_mut(|memory| memory.close_popup());
+                                }
+
+                                shown
+                            } else {
+                                (0..options.len()).collect()
+                            };
+
+                            let highlighted = search_state
+                                .as_ref()
+                                .and_then(|search_state| search_state.highlighted);
+
+                            // Start recording the response for each selectable value.
+                            let mut response = ui.response();
+                            let mut previous_group = None;
+
+                            for (position, &index) in shown.iter().enumerate() {
+                                let (option, text) = &options[index];
+
+                                if let Some(group) = &group {
+                                    let name = group(option);
+
+                                    if previous_group != Some(name) {
+                                        ui.label(egui::RichText::new(name).strong().small());
+                                        previous_group = Some(name);
+                                    }
+                                }
+
+                                let mut selectable = ui.add_enabled(
+                                    is_enabled(option),
+                                    egui::SelectableLabel::new(state == option, text.clone()),
+                                );
+
+                                if highlighted == Some(position) {
+                                    selectable = selectable.highlight();
+
+                                    if moved {
+                                        selectable.scroll_to_me(None);
+                                    }
+                                }
+
+                                if selectable.clicked() && state != option {
+                                    *state = option.clone();
+                                    selectable.mark_changed();
+                                }
+
+                                if selectable.clicked() && search {
+                                    ui.memory_mut(|memory| memory.close_popup());
+                                }
+
+                                response |= selectable;
+                            }
+
+                            if let Some(search_state) = search_state {
+                                ui.data_mut(|data| data.insert_temp(search_id, search_state));
+                            }
+
+                            if changed {
+                                response.mark_changed();
+                            }
+
+                            response
+                        })
+                        .inner
+                })
+                .inner;
+
+            // The search starts over every time the dropdown is opened.
+            if response.is_none() {
+                ui.data_mut(|data| data.remove_temp::<SearchState>(search_id));
+            }
+
+            response
         })
         .inner
     }
 }
+
+/// Move the highlighted position among the `selectable` positions, up or down,
+/// stopping at either end.
+fn navigate(
+    selectable: &[usize],
+    highlighted: Option<usize>,
+    up: bool,
+    down: bool,
+) -> Option<usize> {
+    let current =
+        highlighted.and_then(|highlighted| selectable.iter().position(|&p| p == highlighted));
+
+    let next = match (current, up, down) {
+        (None, _, _) => 0,
+        (Some(current), true, false) => current.saturating_sub(1),
+        (Some(current), false, true) => (current + 1).min(selectable.len().saturating_sub(1)),
+        (Some(current), _, _) => current,
+    };
+
+    selectable.get(next).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::{Event, RawInput};
+
+    const STATES: [&str; 5] = ["Assam", "Goa", "Gujarat", "Delhi", "Puducherry"];
+
+    fn key(key: Key) -> Event {
+        Event::Key {
+            key,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    /// Run a frame with `events`, showing the widgets added by `add`.
+    fn run(ctx: &egui::Context, events: Vec<Event>, add: &mut impl FnMut(&mut egui::Ui)) {
+        let input = RawInput {
+            events,
+            screen_rect: Some(egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(800.0, 600.0),
+            )),
+            ..Default::default()
+        };
+
+        let _ = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| add(ui));
+        });
+    }
+
+    /// Focus a dropdown by pressing tab `tabs` times, and open it with enter.
+    fn open(ctx: &egui::Context, tabs: usize, add: &mut impl FnMut(&mut egui::Ui)) {
+        for _ in 0..tabs {
+            run(ctx, vec![key(Key::Tab)], add);
+        }
+
+        run(ctx, vec![key(Key::Enter)], add);
+        run(ctx, vec![], add);
+    }
+
+    /// Press `keys` one frame after another, and let the dropdown close.
+    fn press(ctx: &egui::Context, keys: &[Key], add: &mut impl FnMut(&mut egui::Ui)) {
+        for &pressed in keys {
+            run(ctx, vec![key(pressed)], add);
+        }
+
+        run(ctx, vec![], add);
+    }
+
+    /// Show a searchable dropdown of [`STATES`], with `Gujarat` disabled.
+    fn show_states(ui: &mut egui::Ui, state: &mut &'static str, id_salt: &str) -> bool {
+        Dropdown::with_state(state)
+            .with_id_salt(id_salt)
+            .with_options(STATES.into_iter())
+            .with_search()
+            .with_disabled(|state| *state == "Gujarat")
+            .with_groups(|state| {
+                if *state == "Delhi" {
+                    "Territories"
+                } else {
+                    "States"
+                }
+            })
+            .show(ui, |state| *state)
+            .is_some_and(|response| response.changed())
+    }
+
+    #[test]
+    fn dropdown_search_and_keys() {
+        let ctx = egui::Context::default();
+        let mut state = "Assam";
+        let mut changed = false;
+        let mut add = |ui: &mut egui::Ui| changed |= show_states(ui, &mut state, "state");
+
+        // The first option matching the search is highlighted, skipping disabled ones
+        open(&ctx, 1, &mut add);
+        run(&ctx, vec![Event::Text("u".into())], &mut add);
+        press(&ctx, &[Key::Enter], &mut add);
+
+        assert!(!ctx.memory(|memory| memory.any_popup_open()));
+        assert!(changed);
+        assert_eq!(state, "Puducherry");
+
+        // The search starts over when it is opened again, and arrows skip disabled options
+        let mut add = |ui: &mut egui::Ui| {
+            show_states(ui, &mut state, "state");
+        };
+
+        open(&ctx, 1, &mut add);
+        press(
+            &ctx,
+            &[Key::ArrowDown, Key::ArrowDown, Key::ArrowUp, Key::Enter],
+            &mut add,
+        );
+
+        assert_eq!(state, "Goa");
+    }
+
+    #[test]
+    fn dropdown_id_salts() {
+        let ctx = egui::Context::default();
+        let mut first = "Assam";
+        let mut second = "Assam";
+
+        // Dropdowns without labels or salts don't share their popup
+        let mut add = |ui: &mut egui::Ui| {
+            for state in [&mut first, &mut second] {
+                Dropdown::with_state(state)
+                    .with_options(STATES.into_iter())
+                    .with_search()
+                    .show(ui, |state| *state);
+            }
+        };
+
+        open(&ctx, 2, &mut add);
+        press(&ctx, &[Key::ArrowDown, Key::Enter], &mut add);
+
+        assert_eq!((first, second), ("Assam", "Goa"));
+    }
+
+    #[test]
+    fn dropdown_navigate() {
+        let selectable = [0, 2, 3];
+
+        assert_eq!(navigate(&selectable, None, false, true), Some(0));
+        assert_eq!(navigate(&selectable, Some(0), false, true), Some(2));
+        assert_eq!(navigate(&selectable, Some(3), false, true), Some(3));
+        assert_eq!(navigate(&selectable, Some(2), true, false), Some(0));
+        assert_eq!(navigate(&selectable, Some(0), true, false), Some(0));
+        assert_eq!(navigate(&[], Some(0), false, true), None);
+    }
+}
@@ -1,7 +1,9 @@
 mod dropdown;
 mod multi_select;
 mod range_selector;
+mod tree_select;
 
 pub use dropdown::*;
 pub use multi_select::*;
 pub use range_selector::*;
+pub use tree_select::*;
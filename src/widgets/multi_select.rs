@@ -1,21 +1,23 @@
+use crate::{types::BitSet, widgets::TreeSelect};
 use egui::Response;
-use rustc_hash::FxHashMap;
 
-/// A widget that displays a check-box for every key
-/// in a [`FxHashMap<String, bool>`].
+/// A widget that displays a check-box for every value
+/// in a list, with their checked states held in a [`BitSet`].
 ///
 /// # Example
 /// ```
-/// # use josaa_orcr::widgets::Multiselect;
-/// # use rustc_hash::FxHashMap;
+/// # use josaa_orcr::{types::BitSet, widgets::Multiselect};
 /// #
 /// # egui::__run_test_ui(|ui| {
-/// let mut state: FxHashMap<String, bool> = FxHashMap::default();
+/// let values = [
+///     "Send me newsletters".to_string(),
+///     "I agree to the terms and services".to_string(),
+/// ];
+/// let mut state = BitSet::new(values.len(), false);
 ///
-/// state.insert("Send me newsletters".into(), true);
-/// state.insert("I agree to the terms and services".into(), false);
+/// state.set(0, true);
 ///
-/// let response = Multiselect::with_state(&mut state)
+/// let response = Multiselect::with_state(&mut state, &values)
 ///     .with_label("Form")
 ///     .show(ui);
 ///
@@ -28,19 +30,86 @@ use rustc_hash::FxHashMap;
 /// };
 /// # });
 /// ```
+///
+/// Values can also be shown as a tree of collapsible groups, where
+/// checking a group checks every value in it:
+/// ```
+/// # use josaa_orcr::{types::BitSet, widgets::Multiselect};
+/// #
+/// # egui::__run_test_ui(|ui| {
+/// let values = ["Apple".to_string(), "Carrot".to_string(), "Banana".to_string()];
+/// let groups = ["Fruits".to_string(), "Vegetables".to_string()];
+/// let mut state = BitSet::new(values.len(), true);
+///
+/// Multiselect::with_state(&mut state, &values)
+///     .with_label("Food")
+///     .with_groups(&groups, &[0, 1, 0])
+///     .show(ui);
+/// # });
+/// ```
+///
+/// Long lists can be searched and sorted. The header counts the checked values, and
+/// the "All", "None" and "Invert" buttons change every value matching the search:
+/// ```
+/// # use josaa_orcr::{types::BitSet, widgets::Multiselect};
+/// #
+/// # egui::__run_test_ui(|ui| {
+/// let values: Vec<String> = ["Mining", "Civil", "Aerospace"].map(String::from).into();
+/// let mut state = BitSet::new(values.len(), true);
+///
+/// // Shown as "Branch (3 / 3 selected)", in the order Aerospace, Civil, Mining
+/// Multiselect::with_state(&mut state, &values)
+///     .with_label("Branch")
+///     .with_search()
+///     .with_alphabetical_order()
+///     .show(ui);
+///
+/// // Or in any other order, given as indices into the values
+/// Multiselect::with_state(&mut state, &values)
+///     .with_label("Branch by length")
+///     .with_order(&[1, 0, 2])
+///     .show(ui);
+/// # });
+/// ```
 pub struct Multiselect<'a> {
     label: String,
-    state: &'a mut FxHashMap<String, bool>,
+    state: &'a mut BitSet,
+    values: &'a [String],
     scroll: bool,
+    search: bool,
+    order: Order<'a>,
+    groups: Option<(&'a [String], &'a [u16])>,
+}
+
+/// A change to every value matching the search.
+#[derive(Clone, Copy)]
+enum Action {
+    All,
+    None,
+    Invert,
+}
+
+/// The order values are shown in.
+enum Order<'a> {
+    /// The order of the values.
+    Given,
+    Alphabetical,
+    /// The values at these indices, in order.
+    Custom(&'a [usize]),
 }
 
 impl<'a> Multiselect<'a> {
     /// Create a new multi-select with the given state.
-    pub fn with_state(state: &'a mut FxHashMap<String, bool>) -> Self {
+    /// The `i`-th bit of `state` is whether `values[i]` is checked.
+    pub fn with_state(state: &'a mut BitSet, values: &'a [String]) -> Self {
         Self {
             label: "".into(),
             scroll: false,
+            search: false,
+            order: Order::Given,
+            groups: None,
             state,
+            values,
         }
     }
 
@@ -57,6 +126,33 @@ impl<'a> Multiselect<'a> {
         self
     }
 
+    /// Show a text box above the values, which hides those not containing its text.
+    pub fn with_search(mut self) -> Self {
+        self.search = true;
+        self
+    }
+
+    /// Show the values in alphabetical order, ignoring case, instead of their order in the list.
+    pub fn with_alphabetical_order(mut self) -> Self {
+        self.order = Order::Alphabetical;
+        self
+    }
+
+    /// Show the values at the indices in `order`, in that order. Values
+    /// whose index isn't in `order` aren't shown.
+    pub fn with_order(mut self, order: &'a [usize]) -> Self {
+        self.order = Order::Custom(order);
+        self
+    }
+
+    /// Show the values in collapsible `groups`, each with a checkbox
+    /// toggling all of its values. `group_of[i]` is the index of the group of `values[i]`.
+    /// Groups without any values aren't shown.
+    pub fn with_groups(mut self, groups: &'a [String], group_of: &'a [u16]) -> Self {
+        self.groups = Some((groups, group_of));
+        self
+    }
+
     /// Render the multi-select.
     ///
     /// Returns [`None`] if it is collapsed, or the response from
@@ -66,13 +162,41 @@ impl<'a> Multiselect<'a> {
         // The container that actually contains the checkboxes.
         // Depending on `self.scroll`, this may be wrapped inside
         // a [`egui::ScrollArea`].
-        let mut inner = |ui: &mut egui::Ui| {
+        let id = ui.make_persistent_id(&self.label);
+        let values = self.values;
+        let state = self.state;
+
+        let header = format!(
+            "{} ({} / {} selected)",
+            self.label,
+            state.count(),
+            values.len()
+        );
+
+        let mut order: Vec<usize> = match self.order {
+            Order::Custom(order) => order.to_vec(),
+            _ => (0..values.len()).collect(),
+        };
+
+        if let Order::Alphabetical = self.order {
+            order.sort_by_cached_key(|&index| values[index].to_lowercase());
+        }
+
+        // Checkboxes for the values at `indices`.
+        let checkboxes = |ui: &mut egui::Ui, state: &mut BitSet, indices: &[usize]| {
             ui.horizontal_wrapped(|ui| {
                 // Start recording the response for each checkbox value.
                 let mut response = ui.response();
 
-                for (value, checked) in self.state.iter_mut() {
-                    response |= ui.checkbox(checked, value);
+                for &index in indices {
+                    let mut checked = state.contains(index);
+                    let checkbox = ui.checkbox(&mut checked, &values[index]);
+
+                    if checkbox.changed() {
+                        state.set(index, checked);
+                    }
+
+                    response |= checkbox;
                 }
 
                 response
@@ -80,15 +204,84 @@ impl<'a> Multiselect<'a> {
             .inner
         };
 
+        let inner = |ui: &mut egui::Ui, state: &mut BitSet, visible: &[usize]| match self.groups {
+            Some((groups, group_of)) => TreeSelect::with_state(state, values, groups, group_of)
+                .with_id_salt(&self.label)
+                .with_order(visible)
+                .show(ui),
+            None => checkboxes(ui, state, visible),
+        };
+
         // The collapsing header that optionally wraps `inner` with a scroll area.
-        egui::CollapsingHeader::new(self.label)
+        // Its id doesn't depend on the count, so that it stays open as values are checked.
+        egui::CollapsingHeader::new(header)
+            .id_salt(&self.label)
             .show(ui, |ui| {
-                if self.scroll {
-                    egui::ScrollArea::vertical().show(ui, inner).inner
-                } else {
-                    inner(ui)
+                // The search is kept in memory, since the widget is rebuilt every frame.
+                let search_id = id.with("search");
+                let mut search: String =
+                    ui.data_mut(|data| data.get_temp(search_id).unwrap_or_default());
+
+                if self.search {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut search)
+                            .hint_text("Search")
+                            .desired_width(160.0),
+                    );
                 }
+
+                let query = search.to_lowercase();
+                let visible: Vec<usize> = order
+                    .iter()
+                    .copied()
+                    .filter(|&index| values[index].to_lowercase().contains(&query))
+                    .collect();
+
+                ui.data_mut(|data| data.insert_temp(search_id, search));
+
+                // Bulk actions, which only change the values matching the search.
+                let mut response = ui
+                    .horizontal(|ui| {
+                        let mut response = ui.response();
+
+                        for (text, action) in [
+                            ("All", Action::All),
+                            ("None", Action::None),
+                            ("Invert", Action::Invert),
+                        ] {
+                            let button = ui.small_button(text);
+
+                            if button.clicked() {
+                                for &index in &visible {
+                                    let checked = match action {
+                                        Action::All => true,
+                                        Action::None => false,
+                                        Action::Invert => !state.contains(index),
+                                    };
+
+                                    state.set(index, checked);
+                                }
+
+                                response.mark_changed();
+                            }
+
+                            response |= button;
+                        }
+
+                        response
+                    })
+                    .inner;
+
+                response |= if self.scroll {
+                    egui::ScrollArea::vertical()
+                        .show(ui, |ui| inner(ui, state, &visible))
+                        .inner
+                } else {
+                    inner(ui, state, &visible)
+                };
+
+                response
             })
-            .body_response
+            .body_returned
     }
 }
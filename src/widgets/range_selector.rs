@@ -13,8 +13,11 @@ use egui::Widget;
 /// let mut state = RankRange::new(2, 4);
 /// let bounds = RankRange::new(1, 12);
 ///
+/// let mut preparatory = true;
+///
 /// let response = RangeSelector::with_state(&mut state, &bounds)
 ///     .with_label("Enter a range")
+///     .with_preparatory(&mut preparatory)
 ///     .ui(ui);
 ///
 /// if response.changed() {
@@ -26,6 +29,7 @@ pub struct RangeSelector<'a> {
     label: String,
     bounds: &'a RankRange,
     state: &'a mut RankRange,
+    preparatory: Option<&'a mut bool>,
 }
 
 impl<'a> RangeSelector<'a> {
@@ -35,6 +39,7 @@ impl<'a> RangeSelector<'a> {
             label: "".into(),
             bounds,
             state,
+            preparatory: None,
         }
     }
 
@@ -43,6 +48,14 @@ impl<'a> RangeSelector<'a> {
         self.label = label.as_ref().into();
         self
     }
+
+    /// Add a checkbox to the right of the range selector, for whether
+    /// preparatory ranks are included. They are outside of every range,
+    /// since they come after every regular rank.
+    pub fn with_preparatory(mut self, state: &'a mut bool) -> Self {
+        self.preparatory = Some(state);
+        self
+    }
 }
 
 impl Widget for RangeSelector<'_> {
@@ -71,6 +84,12 @@ impl Widget for RangeSelector<'_> {
                 .range((self.state.start + 1)..=(self.bounds.end))
                 .ui(ui);
 
+            if let Some(preparatory) = self.preparatory {
+                response |= ui
+                    .checkbox(preparatory, "Preparatory")
+                    .on_hover_text("Include preparatory ranks of PwD candidates, such as 123P");
+            }
+
             response
         })
         .inner
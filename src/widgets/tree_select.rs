@@ -0,0 +1,202 @@
+use crate::types::BitSet;
+use egui::Response;
+
+/// Whether all, some or none of the values in a group are checked.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CheckState {
+    Checked,
+    Indeterminate,
+    Unchecked,
+}
+
+impl CheckState {
+    /// Get the state of a group with the values at `indices`, from their checked states in `state`.
+    /// Empty groups are unchecked.
+    ///
+    /// ```
+    /// # use josaa_orcr::{types::BitSet, widgets::CheckState};
+    /// let mut state = BitSet::new(3, false);
+    /// state.set(1, true);
+    ///
+    /// assert_eq!(CheckState::of(&state, &[0, 1]), CheckState::Indeterminate);
+    /// assert_eq!(CheckState::of(&state, &[1]), CheckState::Checked);
+    /// assert_eq!(CheckState::of(&state, &[0, 2]), CheckState::Unchecked);
+    /// ```
+    pub fn of(state: &BitSet, indices: &[usize]) -> Self {
+        let checked = indices
+            .iter()
+            .filter(|&&index| state.contains(index))
+            .count();
+
+        if checked == 0 {
+            CheckState::Unchecked
+        } else if checked == indices.len() {
+            CheckState::Checked
+        } else {
+            CheckState::Indeterminate
+        }
+    }
+}
+
+/// A widget that displays values in collapsible groups, with a check-box for every value
+/// and a tri-state check-box for every group.
+///
+/// A group is checked when all of its values are, unchecked when none are and
+/// indeterminate otherwise. Checking a group checks all of its values, and
+/// unchecking it unchecks them. Only the values have a checked state, held in a [`BitSet`].
+///
+/// # Example
+/// ```
+/// # use josaa_orcr::{types::BitSet, widgets::TreeSelect};
+/// #
+/// # egui::__run_test_ui(|ui| {
+/// let values: Vec<String> = ["IIT Goa", "NIT Goa", "IIT Patna"].map(String::from).into();
+/// let kinds = ["IIT".to_string(), "NIT".to_string()];
+/// let mut state = BitSet::new(values.len(), true);
+///
+/// // Unchecking both IITs leaves the "IIT" group unchecked as well.
+/// state.set(0, false);
+/// state.set(2, false);
+///
+/// let response = TreeSelect::with_state(&mut state, &values, &kinds, &[0, 1, 0])
+///     .with_id_salt("Institutes")
+///     .with_hover_text(|index| (index == 1).then(|| "Farmagudi, Goa".to_string()))
+///     .show(ui);
+///
+/// if response.changed() {
+///     println!("TreeSelect state was changed.");
+/// }
+/// # });
+/// ```
+pub struct TreeSelect<'a> {
+    id_salt: String,
+    state: &'a mut BitSet,
+    values: &'a [String],
+    groups: &'a [String],
+    group_of: &'a [u16],
+    order: Option<&'a [usize]>,
+    hover_text: Option<Box<dyn Fn(usize) -> Option<String> + 'a>>,
+}
+
+impl<'a> TreeSelect<'a> {
+    /// Create a new tree select with the given state.
+    /// The `i`-th bit of `state` is whether `values[i]` is checked,
+    /// and `group_of[i]` is the index of its group in `groups`.
+    pub fn with_state(
+        state: &'a mut BitSet,
+        values: &'a [String],
+        groups: &'a [String],
+        group_of: &'a [u16],
+    ) -> Self {
+        Self {
+            id_salt: "".into(),
+            order: None,
+            hover_text: None,
+            state,
+            values,
+            groups,
+            group_of,
+        }
+    }
+
+    /// Set the salt of the ids which keep whether each group is open.
+    /// This should be unique.
+    pub fn with_id_salt(mut self, id_salt: impl AsRef<str>) -> Self {
+        self.id_salt = id_salt.as_ref().into();
+        self
+    }
+
+    /// Show the values at the indices in `order`, in that order. Values
+    /// whose index isn't in `order` aren't shown.
+    pub fn with_order(mut self, order: &'a [usize]) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Show the text returned for a value's index when it is hovered, if any.
+    pub fn with_hover_text(mut self, hover_text: impl Fn(usize) -> Option<String> + 'a) -> Self {
+        self.hover_text = Some(Box::new(hover_text));
+        self
+    }
+
+    /// Render the tree select. Groups without any values shown are skipped.
+    ///
+    /// Use [`Response::changed()`] on the returned response to check for state changes.
+    pub fn show(self, ui: &mut egui::Ui) -> Response {
+        let id = ui.make_persistent_id(&self.id_salt);
+        let values = self.values;
+        let state = self.state;
+        let order: Vec<usize> = match self.order {
+            Some(order) => order.to_vec(),
+            None => (0..values.len()).collect(),
+        };
+
+        ui.vertical(|ui| {
+            let mut response = ui.response();
+
+            for (group, name) in self.groups.iter().enumerate() {
+                let indices: Vec<usize> = order
+                    .iter()
+                    .copied()
+                    .filter(|&index| self.group_of[index] as usize == group)
+                    .collect();
+
+                if indices.is_empty() {
+                    continue;
+                }
+
+                let check_state = CheckState::of(state, &indices);
+                let checked = indices
+                    .iter()
+                    .filter(|&&index| state.contains(index))
+                    .count();
+
+                egui::collapsing_header::CollapsingState::load_with_default_open(
+                    ui.ctx(),
+                    id.with(name),
+                    false,
+                )
+                .show_header(ui, |ui| {
+                    // Indeterminate groups become fully checked.
+                    let mut all = check_state == CheckState::Checked;
+                    let checkbox = ui.add(
+                        egui::Checkbox::new(
+                            &mut all,
+                            format!("{name} ({checked}/{})", indices.len()),
+                        )
+                        .indeterminate(check_state == CheckState::Indeterminate),
+                    );
+
+                    if checkbox.changed() {
+                        for &index in &indices {
+                            state.set(index, all);
+                        }
+                    }
+
+                    response |= checkbox;
+                })
+                .body_unindented(|ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        for &index in &indices {
+                            let mut checked = state.contains(index);
+                            let mut checkbox = ui.checkbox(&mut checked, &values[index]);
+
+                            if let Some(text) = self.hover_text.as_ref().and_then(|f| f(index)) {
+                                checkbox = checkbox.on_hover_text(text);
+                            }
+
+                            if checkbox.changed() {
+                                state.set(index, checked);
+                            }
+
+                            response |= checkbox;
+                        }
+                    });
+                });
+            }
+
+            response
+        })
+        .inner
+    }
+}